@@ -1,14 +1,15 @@
 use std::process::exit;
 
-use crate::error;
+use crate::error::{self, Diagnostic, Severity};
 pub static mut EASY_MODE: bool = false;
 pub static mut TOGGLE_CASE: i32 = 0;
 #[derive(PartialEq, Eq, Debug)]
 pub struct ParsedArgs {
-    pub repl: bool,   // inerative mode
-    pub file: String, // file to read/write
-    pub force: bool,  // if true, overwrites file
-    pub log: bool,    // if true, logs to file
+    pub repl: bool,           // inerative mode
+    pub file: String,         // file to read/write
+    pub force: bool,          // if true, overwrites file
+    pub log: bool,            // if true, logs to file
+    pub include: Vec<String>, // extra directories to search for modules
 }
 
 impl ParsedArgs {
@@ -18,6 +19,7 @@ impl ParsedArgs {
             file,
             force: false,
             log: false,
+            include: Vec::new(),
         }
     }
 }
@@ -30,7 +32,7 @@ pub fn get_string_args(args: &[String]) -> (usize, ParsedArgs) {
         return (0, ParsedArgs::new(true, String::new()));
     } else if args[1].ends_with(".umpl") {
         // make sure it's a .umpl file
-        to_return.file = args[1].to_string(); // if it is, then set file to the file name
+        to_return.file.clone_from(&args[1]); // if it is, then set file to the file name
         to_return.repl = false; // and set repl to false
         index += 1; // and increment index
         let file_len = to_return.file.strip_suffix(".umpl").unwrap().len(); // get the length of the file name without the .umpl
@@ -53,12 +55,13 @@ pub fn get_string_args(args: &[String]) -> (usize, ParsedArgs) {
             }
             usage(); // if not a flag, then its not one of the args we want so print usage and exit
         }
-    };
+    }
     (index, to_return)
 }
 #[allow(clippy::cast_possible_wrap)]
 pub fn get_dash_args(args: &[String], start_index: usize, args_struct: &mut ParsedArgs) {
-    args[start_index..].iter().for_each(|arg| {
+    let mut args = args[start_index..].iter();
+    while let Some(arg) = args.next() {
         // for each arg after the start index
         if arg.starts_with('-') {
             // if it starts with a dash check if its a correct flag and set the appropriate field if not print usage and exit
@@ -79,12 +82,27 @@ pub fn get_dash_args(args: &[String], start_index: usize, args_struct: &mut Pars
                         file => file.len(),
                     };
                     unsafe { TOGGLE_CASE = num as i32 };
+                } else if char_part_arg == 'I' {
+                    // the directory is the next argument
+                    match args.next() {
+                        Some(dir) => args_struct.include.push(dir.clone()),
+                        None => error::fatal(&Diagnostic::new(
+                            Severity::Error,
+                            "option I requires a directory",
+                        )),
+                    }
+                    break;
                 } else if char_part_arg == 't' {
                     let number: i32 = arg.split_once('=').map_or_else(
-                        || error::error(0, "option t requires an =number"),
+                        || {
+                            error::fatal(&Diagnostic::new(
+                                Severity::Error,
+                                "option t requires an =number",
+                            ))
+                        },
                         |n| match n.1.parse() {
                             Ok(value) => value,
-                            Err(error) => error::error(0, error),
+                            Err(error) => error::fatal(&Diagnostic::new(Severity::Error, error)),
                         },
                     );
                     unsafe {
@@ -98,7 +116,7 @@ pub fn get_dash_args(args: &[String], start_index: usize, args_struct: &mut Pars
         } else {
             usage();
         }
-    });
+    }
 }
 
 fn usage() {
@@ -110,7 +128,8 @@ fn usage() {
         -r, -i: interactive mode
         -h: help
         -f: force
-        -t=number: toggle case"
+        -t=number: toggle case
+        -I dir: search dir for modules"
             );
         } else {
             eprintln!("Segmentation fault (core dumped)");
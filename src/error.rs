@@ -1,39 +1,300 @@
-use crate::cli::EASY_MODE;
-use std::{fmt::Display, process::exit};
+use crate::{cli::EASY_MODE, token::Span};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Write},
+    process::exit,
+};
 
-pub fn error<T: Display>(line: i32, message: T) -> ! {
-    let where_ = "";
-    let message = message.to_string();
-    let mut message = message.as_str();
-    unsafe {
-        if EASY_MODE {
-        } else {
-            message = "Segmentation fault (core dumped)";
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    pub primary: bool,
+    /// the line of code the span starts on, filled in by [`Diagnostic::with_sources`]
+    pub source_line: Option<String>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// the primary label (if any) is always first
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new<T: Display>(severity: Severity, message: T) -> Self {
+        Self {
+            severity,
+            message: message.to_string(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn error<T: Display>(span: &Span, message: T) -> Self {
+        Self::new(Severity::Error, message).with_primary_label(span, "")
+    }
+
+    pub fn warning<T: Display>(span: &Span, message: T) -> Self {
+        Self::new(Severity::Warning, message).with_primary_label(span, "")
+    }
+
+    #[must_use]
+    pub fn with_primary_label<T: Display>(mut self, span: &Span, message: T) -> Self {
+        self.labels.retain(|label| !label.primary);
+        self.labels.insert(
+            0,
+            Label {
+                span: span.clone(),
+                message: message.to_string(),
+                primary: true,
+                source_line: None,
+            },
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn with_label<T: Display>(mut self, span: &Span, message: T) -> Self {
+        self.labels.push(Label {
+            span: span.clone(),
+            message: message.to_string(),
+            primary: false,
+            source_line: None,
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn with_note<T: Display>(mut self, note: T) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// gives each label the line of code it points at, `sources` is the source of each file by name
+    #[must_use]
+    pub fn with_sources(mut self, sources: &HashMap<String, String>) -> Self {
+        for label in &mut self.labels {
+            if label.source_line.is_some() {
+                continue;
+            }
+            label.source_line = sources.get(&label.span.filename).and_then(|source| {
+                source
+                    .lines()
+                    .nth(usize::try_from(label.span.start_line - 1).ok()?)
+                    .map(ToString::to_string)
+            });
+        }
+        self
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        self.labels
+            .first()
+            .filter(|label| label.primary)
+            .map(|label| &label.span)
+    }
+
+    /// prints the diagnostic to stderr, unless easy mode is on all you get is a segfault,
+    /// and warnings are not worth a segfault so they are only shown in easy mode
+    pub fn report(&self) {
+        if unsafe { EASY_MODE } {
+            eprint!("{self}");
+        } else if self.severity == Severity::Error {
+            eprintln!(
+                "[line: {}], Error: Segmentation fault (core dumped)",
+                self.span().map_or(0, |span| span.start_line)
+            );
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.severity, self.message)?;
+        let gutter = self
+            .labels
+            .iter()
+            .map(|label| label.span.end_line.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let mut last: Option<&Span> = None;
+        for label in &self.labels {
+            let span = &label.span;
+            match last {
+                Some(last) if last.filename == span.filename => {
+                    if (last.start_line - span.start_line).abs() > 1 {
+                        writeln!(f, "{:gutter$} ...", "")?;
+                    }
+                }
+                _ => {
+                    let arrow = if label.primary { "-->" } else { ":::" };
+                    writeln!(f, "{:gutter$}{arrow} {span}", "")?;
+                    writeln!(f, "{:gutter$} |", "")?;
+                }
+            }
+            last = Some(span);
+            let Some(source_line) = &label.source_line else {
+                if !label.message.is_empty() {
+                    writeln!(f, "{:gutter$} = {}", "", label.message)?;
+                }
+                continue;
+            };
+            writeln!(f, "{:>gutter$} | {source_line}", span.start_line)?;
+            // the underline stops at the end of the first line of multi line spans
+            let start = usize::try_from(span.start_column - 1).unwrap_or(0);
+            let line_length = source_line.chars().count();
+            let end = if span.end_line == span.start_line {
+                usize::try_from(span.end_column - 1).unwrap_or(0)
+            } else {
+                line_length
+            }
+            .clamp(start + 1, line_length.max(start + 1));
+            let mut underline = String::new();
+            let marker = if label.primary { '^' } else { '-' };
+            write!(
+                underline,
+                "{:start$}{}",
+                "",
+                marker.to_string().repeat(end - start)
+            )?;
+            if label.message.is_empty() {
+                writeln!(f, "{:gutter$} | {underline}", "")?;
+            } else {
+                writeln!(f, "{:gutter$} | {underline} {}", "", label.message)?;
+            }
+        }
+        for note in &self.notes {
+            writeln!(f, "{:gutter$} = note: {note}", "")?;
         }
+        Ok(())
     }
-    if message.is_empty() {
-        message = "Segmentation fault (core dumped)";
+}
+
+/// an error that stops a program, returned up the stack instead of exiting the process
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub enum UmplError {
+    /// a lex, parse or runtime error
+    Diagnostic(Diagnostic),
+    /// every error found in one pass, like all the syntax errors in a file
+    Diagnostics(Vec<Diagnostic>),
+    /// the program asked to stop (with `exit`) with the given exit code
+    Exit(i32),
+}
+
+impl From<Diagnostic> for UmplError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Self::Diagnostic(diagnostic)
+    }
+}
+
+impl Display for UmplError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Diagnostic(diagnostic) => write!(f, "{diagnostic}"),
+            Self::Diagnostics(diagnostics) => {
+                for (i, diagnostic) in diagnostics.iter().enumerate() {
+                    if i != 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{diagnostic}")?;
+                }
+                Ok(())
+            }
+            Self::Exit(code) => write!(f, "exited with code {code}"),
+        }
     }
-    eprintln!("[line: {line}], Error{where_}: {message}");
+}
+
+impl std::error::Error for UmplError {}
+
+impl UmplError {
+    /// gives every diagnostic in the error the lines of code it points at, see [`Diagnostic::with_sources`]
+    #[must_use]
+    pub fn with_sources(self, sources: &HashMap<String, String>) -> Self {
+        match self {
+            Self::Diagnostic(diagnostic) => Self::Diagnostic(diagnostic.with_sources(sources)),
+            Self::Diagnostics(diagnostics) => Self::Diagnostics(
+                diagnostics
+                    .into_iter()
+                    .map(|diagnostic| diagnostic.with_sources(sources))
+                    .collect(),
+            ),
+            Self::Exit(code) => Self::Exit(code),
+        }
+    }
+
+    /// reports every diagnostic in the error, an exit has nothing to report
+    pub fn report(&self) {
+        match self {
+            Self::Diagnostic(diagnostic) => diagnostic.report(),
+            Self::Diagnostics(diagnostics) => {
+                for (i, diagnostic) in diagnostics.iter().enumerate() {
+                    if i != 0 && unsafe { EASY_MODE } {
+                        eprintln!();
+                    }
+                    diagnostic.report();
+                }
+            }
+            Self::Exit(_) => {}
+        }
+    }
+}
+
+/// reports the diagnostic and exits
+pub fn fatal(diagnostic: &Diagnostic) -> ! {
+    diagnostic.report();
     exit(1);
 }
 
+pub fn error<T: Display>(span: &Span, message: T) -> UmplError {
+    let message = message.to_string();
+    let message = if message.is_empty() {
+        "Segmentation fault (core dumped)"
+    } else {
+        message.as_str()
+    };
+    Diagnostic::error(span, message).into()
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub fn arg_error<T: Display>(
     num_args: u32,
     given_args: u32,
     function: T,
     at_least: bool,
-    line: i32,
-) {
+    span: &Span,
+) -> Result<(), UmplError> {
     if at_least {
         if num_args > given_args {
-            error(
-                line,
+            return Err(error(
+                span,
                 format!("{function} requires at least {num_args} arguments"),
-            );
+            ));
         }
     } else if num_args != given_args {
-        error(line, format!("{function} requires {num_args} arguments"));
+        return Err(error(
+            span,
+            format!("{function} requires {num_args} arguments"),
+        ));
     }
+    Ok(())
 }
@@ -2,20 +2,28 @@ use log::{debug, info};
 
 use std::{
     cell::{RefCell, RefMut},
-    collections::HashMap,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    env,
     fmt::{self, Display},
     fs::{self, File, OpenOptions},
-    io::{Read, Write},
-    mem::swap,
-    rc::Rc,
+    io::{self, Read, Write},
+    iter, mem,
+    path::{Path, PathBuf},
+    rc::{Rc, Weak},
 };
 
 use crate::{
-    error::{arg_error, error},
+    error::{arg_error, error, Diagnostic, UmplError},
+    lexer::{is_module_name, Lexer},
     parser::rules::{
-        Ast, Call, Declaration, DeclarationType, Function, Identifier, If, LiteralNode, Return,
+        Ast, Block, Break, Call, Continue, Declaration, DeclarationNode, Function,
+        FunctionIdentifier, Identifier, If, IfNode, Lambda, List, Literal, Loop, Match, MatchArm,
+        MatchNode, Pattern, PrintType, Return, Try, TryNode,
     },
-    token::TokenType,
+    parser::Parser,
+    token::{Span, TokenType},
+    value::{Potato, Value},
 };
 
 pub fn read_file(file_name: &str) -> Result<String, Box<dyn std::error::Error>> {
@@ -45,406 +53,371 @@ pub fn write_file(
         _ => Err("Invalid mode")?,
     }
 }
-#[derive(PartialEq, Debug, Clone)]
-pub struct NewExpression {
-    pub inside: LiteralOrFile,
-    pub print: bool,
-    pub line: i32,
-    pub new_line: bool,
-}
 
-impl Display for NewExpression {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            if self.print {
-                if self.new_line {
-                    format!("{}\n", self.inside)
-                } else {
-                    format!("{}", self.inside)
-                }
-            } else {
-                String::new()
-            }
-        )
+/// prints the value of an expression according to how it was closed: `>` prints it with a new line, `>>` without and `<` not at all
+pub fn print_value(value: &impl Display, print: &PrintType, span: &Span) -> Result<(), UmplError> {
+    match print {
+        PrintType::PrintLn => println!("{value}"),
+        PrintType::Print => {
+            print!("{value}");
+            io::stdout()
+                .flush()
+                .map_err(|_| error(span, "Error flushing stdout"))?;
+        }
+        PrintType::None => {}
     }
+    Ok(())
 }
 
-#[derive(PartialEq, Clone, Debug)]
-pub enum LitOrList {
-    Identifier(Rc<RefCell<NewList>>),
-    Literal(LiteralOrFile),
-}
+/// the new value of a variable changed with `keyword`, like addwith
+/// the longest string multiplywith makes, a longer one would fail to be allocated instead of giving an error
+const MAX_STRING_LEN: usize = 1 << 24;
 
-impl Display for LitOrList {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Identifier(i) => write!(f, "{}", i.borrow()),
-            Self::Literal(l) => write!(f, "{l}"),
+fn update_value(
+    keyword: &TokenType,
+    var: Value,
+    value: Value,
+    span: &Span,
+) -> Result<Value, UmplError> {
+    Ok(match (keyword, var, value) {
+        (TokenType::AddWith, Value::Number(num), Value::Number(num2)) => Value::Number(num + num2),
+        (
+            TokenType::AddWith,
+            Value::String(mut s),
+            value @ (Value::String(_) | Value::Number(_) | Value::Boolean(_) | Value::Hempty),
+        ) => {
+            s.push_str(&value.to_string());
+            Value::String(s)
         }
-    }
-}
-#[derive(PartialEq, Clone, Debug)]
-pub struct NewList {
-    pub car: LitOrList,
-    pub cdr: LitOrList,
-}
-
-impl NewList {
-    pub fn new(mut thing: Vec<LiteralOrFile>) -> Self {
-        Self {
-            car: LitOrList::Literal(thing.remove(0)),
-            cdr: LitOrList::Literal(thing.remove(0)),
+        (TokenType::SubtractWith, Value::Number(num), Value::Number(num2)) => {
+            Value::Number(num - num2)
         }
-    }
-
-    pub fn set_last(&mut self, new_item: LitOrList) {
-        match &self.cdr {
-            LitOrList::Identifier(i) => i.borrow_mut().set_last(new_item),
-            LitOrList::Literal(i) => {
-                // check if cdr is hempty
-                if i == &LiteralOrFile::Literal(LiteralNode::Hempty) {
-                    self.cdr = new_item;
-                }
+        (TokenType::MultiplyWith, Value::Number(num), Value::Number(num2)) => {
+            Value::Number(num * num2)
+        }
+        (TokenType::MultiplyWith, Value::String(s), Value::Number(num)) => {
+            if !(num >= 0.0 && num.fract() == 0.0) {
+                return Err(error(
+                    span,
+                    format!("{keyword} can only repeat a string a whole number of times that is not negative, found {num}"),
+                ));
             }
+            if s.len() as f64 * num > MAX_STRING_LEN as f64 {
+                return Err(error(
+                    span,
+                    format!("{keyword} would make a string longer than {MAX_STRING_LEN} bytes"),
+                ));
+            }
+            Value::String(s.repeat(num as usize))
         }
-    }
+        (TokenType::DivideWith, Value::Number(num), Value::Number(num2)) => {
+            Value::Number(num / num2)
+        }
+        (keyword, var, value) => {
+            return Err(error(
+                span,
+                format!(
+                    "cannot use {keyword} on a {} with a {}",
+                    var.get_type(),
+                    value.get_type()
+                ),
+            ))
+        }
+    })
 }
 
-impl Display for NewList {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "with: [{}, {}]", self.car, self.cdr)
-    }
+#[derive(Debug)]
+pub struct Scope {
+    pub vars: HashMap<String, Value>,
+    pub function: HashMap<char, Rc<Potato>>,
+    pub parent_scope: Option<Rc<RefCell<Self>>>,
+    pub files: HashMap<String, File>,
+    pub open_modules: HashMap<String, Rc<Module>>,
+    /// the functions and variables that are only visible inside this scope's module
+    pub private: HashSet<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum LiteralOrFile {
-    Literal(LiteralNode),
-    File(String),
+#[derive(Debug)]
+pub struct Module {
+    /// parent scope will be empty unless its an inline module
+    pub scope: Rc<RefCell<Scope>>,
+    pub missing_function_handler: Option<Rc<Potato>>,
 }
 
-impl LiteralOrFile {
-    pub fn get_file(self, line: i32, keyword: &TokenType) -> String {
-        match self {
-            Self::File(file) => file,
-            _ => {
-                error(line, format!("{keyword} requires a file"));
-            }
+impl Module {
+    pub const fn new(scope: Rc<RefCell<Scope>>) -> Self {
+        Self {
+            scope,
+            missing_function_handler: None,
         }
     }
-    pub fn get_string(self, line: i32, keyword: &TokenType) -> String {
-        match self {
-            Self::Literal(LiteralNode::String(lit)) => lit,
-            _ => {
-                error(line, format!("{keyword} requires a string"));
-            }
-        }
+
+    pub fn set_missing_function_handler(&mut self, missing_function_handler: Option<Rc<Potato>>) {
+        self.missing_function_handler = missing_function_handler;
     }
-    pub fn get_number(self, line: i32, keyword: &TokenType) -> f64 {
-        match self {
-            Self::Literal(LiteralNode::Number(lit)) => lit,
-            _ => {
-                error(line, format!("{keyword} requires a number"));
-            }
+
+    /// finds a public function of the module
+    pub fn get_function(&self, name: char) -> Option<Rc<Potato>> {
+        if self.is_private(&name.to_string()) {
+            return None;
         }
+        self.scope.borrow().get_function(name, &[])
     }
-    pub fn get_bool(self, line: i32, keyword: &TokenType) -> bool {
-        match self {
-            Self::Literal(LiteralNode::Boolean(lit)) => lit,
-            _ => {
-                error(line, format!("{keyword} requires a boolean"));
-            }
-        }
+
+    pub fn is_private(&self, name: &str) -> bool {
+        self.scope.borrow().private.contains(name)
     }
-}
 
-impl Display for LiteralOrFile {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Literal(lit) => write!(f, "{lit}"),
-            Self::File(file) => write!(f, "{file}"),
+    /// finds a public variable of the module,
+    /// a list is copied so changing it does not change the module's list
+    pub fn get_var(&self, name: &str, module_name: &str, span: &Span) -> Result<Value, UmplError> {
+        if self.is_private(name) {
+            return Err(error(
+                span,
+                format!("variable {name} is private to module {module_name}"),
+            ));
         }
+        self.scope
+            .borrow()
+            .vars
+            .get(name)
+            .map(Value::deep_copy)
+            .ok_or_else(|| {
+                error(
+                    span,
+                    format!("variable {name} not found in module {module_name}"),
+                )
+            })
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
-pub struct NewVairable {
-    pub value: LiteralOrFile,
+/// splits `m$n$var` into the module path and the variable, if the name is from a module
+fn module_path(name: &str) -> Option<(&str, &str)> {
+    name.split('.')
+        .next()
+        .unwrap_or(name)
+        .rsplit_once('$')
+        .filter(|(path, _)| !path.is_empty())
 }
 
-impl NewVairable {
-    pub const fn new(value: LiteralOrFile) -> Self {
-        Self { value }
-    }
-}
+/// a module that declares a potato with this name has it called for any function it is missing,
+/// with the name of the missing function as the first argument
+pub const MISSING_FUNCTION_HANDLER: char = '🤷';
 
-impl Display for NewVairable {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "with: {}", self.value)
-    }
-}
-#[derive(PartialEq, Clone, Debug)]
-pub enum NewIdentifierType {
-    List(Rc<RefCell<NewList>>),
-    Vairable(NewVairable),
+/// the modules shared by every file in a run
+#[derive(Debug)]
+pub struct Modules {
+    /// directories searched after the importing file's own, from `-I` and then `UMPL_PATH`, the current directory is searched last
+    pub search_path: Vec<PathBuf>,
+    /// every module loaded so far by canonical path, importing a file twice gives the same module
+    loaded: HashMap<PathBuf, Rc<Module>>,
+    /// the files being loaded, outermost first, with their display name and where they were imported
+    loading: Vec<(PathBuf, String, Option<Span>)>,
+    /// the warnings from parsing the modules
+    warnings: Vec<Diagnostic>,
+    /// the source of every module by its display name, for showing the code a diagnostic points at
+    sources: HashMap<String, String>,
+    /// the scopes kept alive by function values, by address
+    kept_alive: HashMap<*const RefCell<Scope>, Weak<RefCell<Scope>>>,
 }
 
-impl NewIdentifierType {
-    pub fn to_vec_literal(self, line: i32) -> Vec<LiteralOrFile> {
-        match self {
-            Self::Vairable(v) => vec![v.value],
-            _ => error(line, "cannot convert to vec"),
+impl Modules {
+    pub fn new() -> Self {
+        Self {
+            search_path: env::var_os("UMPL_PATH")
+                .map(|paths| env::split_paths(&paths).collect())
+                .unwrap_or_default(),
+            loaded: HashMap::new(),
+            loading: Vec::new(),
+            warnings: Vec::new(),
+            sources: HashMap::new(),
+            kept_alive: HashMap::new(),
         }
     }
-}
 
-#[derive(Debug)]
-pub struct Scope {
-    pub vars: HashMap<String, NewIdentifierType>,
-    pub function: HashMap<char, (Vec<Ast>, f64, bool)>,
-    pub parent_scope: Option<Box<Scope>>,
-    pub files: HashMap<String, File>,
-    pub open_modules: [Option<Box<Module>>; 26],
-}
+    /// remembers a scope that a function value keeps alive, so it can be emptied when the program ends
+    fn keep_alive(&mut self, scope: &Weak<RefCell<Scope>>) {
+        // forget the scopes that were freed before growing
+        if self.kept_alive.len() == self.kept_alive.capacity() {
+            self.kept_alive.retain(|_, scope| scope.strong_count() > 0);
+        }
+        self.kept_alive
+            .entry(scope.as_ptr())
+            .or_insert_with(|| Weak::clone(scope));
+    }
 
-#[derive(Debug)]
-pub struct Module {
-    /// parent scope will be empty unless its an inline module
-    pub scope: Scope,
-    pub missing_function_handler: Option<(Vec<Ast>, f64, bool)>,
-}
+    fn resolve(&self, importer: &Path, path: &str) -> Option<PathBuf> {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            return path.is_file().then(|| path.to_path_buf());
+        }
+        let importer_dir = importer.parent().map(Path::to_path_buf).unwrap_or_default();
+        // the current directory is last, that is where modules were looked for before the others
+        let current_dir = PathBuf::new();
+        iter::once(&importer_dir)
+            .chain(&self.search_path)
+            .chain(iter::once(&current_dir))
+            .map(|dir| dir.join(path))
+            .find(|path| path.is_file())
+    }
 
-impl Module {
-    pub fn new(scope: Scope) -> Self {
-        Self {
-            scope,
-            missing_function_handler: None,
+    fn cycle_error(&self, start: usize, name: &str, span: &Span) -> UmplError {
+        let cycle = &self.loading[start..];
+        let chain = cycle
+            .iter()
+            .map(|(_, name, _)| name.as_str())
+            .chain(iter::once(name))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        let mut diagnostic =
+            Diagnostic::error(span, format!("module cycle, {name} imports itself"))
+                .with_primary_label(span, format!("{name} is already being loaded"));
+        for (_, name, site) in &cycle[1..] {
+            if let Some(site) = site {
+                diagnostic = diagnostic.with_label(site, format!("{name} imported here"));
+            }
         }
+        diagnostic
+            .with_note(format!("import chain: {chain}"))
+            .into()
     }
+}
 
-    pub fn set_missing_function_handler(
-        &mut self,
-        missing_function_handler: Option<(Vec<Ast>, f64, bool)>,
-    ) {
-        self.missing_function_handler = missing_function_handler;
+impl Default for Modules {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    pub fn get_function(&self, name: char, path: &[char]) -> (Vec<Ast>, f64, bool) {
-        self.scope.get_function(name, path).unwrap_or(
-            self.missing_function_handler
-                .clone()
-                .unwrap_or_else(|| todo!("defualt handler")),
-        )
+impl Drop for Modules {
+    // a function value can be stored in the scope it keeps alive, or in one of the scope's parents,
+    // then the scope would never be freed, so everything the kept alive scopes hold is let go of
+    fn drop(&mut self) {
+        for scope in self.kept_alive.values().filter_map(Weak::upgrade) {
+            let held = {
+                let mut scope = scope.borrow_mut();
+                (
+                    mem::take(&mut scope.vars),
+                    mem::take(&mut scope.function),
+                    scope.parent_scope.take(),
+                    mem::take(&mut scope.open_modules),
+                )
+            };
+            drop(held);
+        }
     }
 }
 
 impl Scope {
-    const EMPTY_MODULE: Option<Box<Module>> = None;
     pub fn new() -> Self {
-        let open_modules = [Self::EMPTY_MODULE; 26];
         Self {
             vars: HashMap::new(),
             function: HashMap::new(),
             parent_scope: None,
             files: HashMap::new(),
-            open_modules,
+            open_modules: HashMap::new(),
+            private: HashSet::new(),
         }
     }
-    pub fn new_with_parent(parent: Box<Self>) -> Self {
-        let open_modules = [Self::EMPTY_MODULE; 26];
+    pub fn new_with_parent(parent: Rc<RefCell<Self>>) -> Self {
         Self {
             vars: HashMap::new(),
             function: HashMap::new(),
             parent_scope: Some(parent),
             files: HashMap::new(),
-            open_modules,
+            open_modules: HashMap::new(),
+            private: HashSet::new(),
         }
     }
     pub fn set_var(
         &mut self,
         name: &str,
-        value: &mut Vec<LiteralOrFile>,
+        value: Value,
         recurse: bool,
-        line: i32,
-    ) {
+        span: &Span,
+    ) -> Result<(), UmplError> {
         // the reason for this being its own method vs using the set method is because it will be easier to use/implemnet getting variable from different scopes
-        // and also less typing instead of creating a NewIdentifierType you just pass in a vector of LiteralNode
-        debug!("setting var: {} to: {:?}", name, value);
-        let new_val: NewIdentifierType = match value.len() {
-            0 => error(line, "expected Identifier, got empty list"),
-            1 => NewIdentifierType::Vairable(NewVairable::new(value.clone().remove(0))),
-            2 => NewIdentifierType::List(Rc::new(RefCell::new(NewList::new(value.clone())))),
-            _ => error(
-                line,
-                "expected Identifier, got list with more than 2 elements",
-            ),
-        };
-        match name {
-            name if name.ends_with(".car") | name.ends_with(".cdr") => {
-                let new_name = name.trim_end_matches(".car").trim_end_matches(".cdr");
-                if recurse {
-                    if self.has_var(new_name, false) {
-                        let new_var = match self.get_var(new_name, line) {
-                            NewIdentifierType::List(list) => list,
-                            _ => error(line, "expected list"),
-                        };
-                        if name.ends_with(".cdr") {
-                            new_var.borrow_mut().cdr = match new_val {
-                                NewIdentifierType::List(list) => LitOrList::Identifier(list),
-                                NewIdentifierType::Vairable(var) => LitOrList::Literal(var.value),
-                            };
-                        } else {
-                            new_var.borrow_mut().car = match new_val {
-                                NewIdentifierType::List(list) => LitOrList::Identifier(list),
-                                NewIdentifierType::Vairable(var) => LitOrList::Literal(var.value),
-                            };
-                        }
-                        self.vars
-                            .insert(new_name.to_string(), NewIdentifierType::List(new_var));
-                    } else {
-                        self.parent_scope.as_mut().map_or_else(
-                            || error(line, "variable not found"),
-                            |parent| {
-                                parent.set_var(
-                                    name,
-                                    &mut new_val.to_vec_literal(line),
-                                    recurse,
-                                    line,
-                                );
-                            },
-                        );
-                    }
-                } else {
-                    let new_var: Rc<RefCell<NewList>> = match self.get_var(new_name, line) {
-                        NewIdentifierType::List(list) => list,
-                        _ => error(line, "expected list"),
-                    };
-                    if name.ends_with(".cdr") {
-                        new_var.borrow_mut().cdr = match new_val {
-                            NewIdentifierType::List(list) => LitOrList::Identifier(list),
-                            NewIdentifierType::Vairable(var) => LitOrList::Literal(var.value),
-                        };
-                    } else {
-                        new_var.borrow_mut().car = match new_val {
-                            NewIdentifierType::List(list) => LitOrList::Identifier(list),
-                            NewIdentifierType::Vairable(var) => LitOrList::Literal(var.value),
-                        };
-                    }
-                    self.vars
-                        .insert(new_name.to_string(), NewIdentifierType::List(new_var));
-                }
-            }
-            _ => {
-                if recurse {
-                    if self.has_var(name, false) {
-                        self.vars.insert(name.to_string(), new_val);
-                    } else {
-                        self.parent_scope.as_mut().map_or_else(
-                            || error(line, "variable not found"),
-                            |parent| parent.set_var(name, value, recurse, line),
-                        );
-                    }
-                } else {
-                    self.vars.insert(name.to_string(), new_val);
-                }
-            }
-        }
-    }
-    pub fn set_list(&mut self, name: &str, value: NewList, recurse: bool, line: i32) {
-        if recurse {
-            if self.has_var(name, false) {
-                self.vars.insert(
-                    name.to_string(),
-                    NewIdentifierType::List(Rc::new(RefCell::new(value))),
-                );
+        debug!("setting var: {name} to: {value:?}");
+        if name.ends_with(".car") || name.ends_with(".cdr") {
+            // lists are shared, so changing the pair changes it for every variable holding it
+            let Value::Cons(cons) = self.get_var(&name[..name.len() - 4], span)? else {
+                return Err(error(span, "expected list"));
+            };
+            if name.ends_with(".car") {
+                cons.borrow_mut().car = value;
             } else {
-                self.parent_scope.as_mut().map_or_else(
-                    || error(line, "variable not found"),
-                    |parent| parent.set_list(name, value, recurse, line),
-                );
+                cons.borrow_mut().cdr = value;
             }
-        } else {
-            self.vars.insert(
-                name.to_string(),
-                NewIdentifierType::List(Rc::new(RefCell::new(value))),
-            );
+            return Ok(());
+        }
+        if recurse && !self.has_var(name, false) {
+            let Some(parent) = &self.parent_scope else {
+                return Err(error(span, "variable not found"));
+            };
+            return parent.borrow_mut().set_var(name, value, recurse, span);
         }
+        self.vars.insert(name.to_string(), value);
+        Ok(())
     }
-    pub fn get_var(&mut self, name: &str, line: i32) -> NewIdentifierType {
+    pub fn get_var(&self, name: &str, span: &Span) -> Result<Value, UmplError> {
         // the reason for this being its own method vs using the get method is because it will be easier to use/implemnet getting variable from different scopes
         if name.ends_with(".car") || name.ends_with(".cdr") {
-            if let NewIdentifierType::List(list) = self.get_var(&name[..name.len() - 4], line) {
-                if name.ends_with(".car") {
-                    match &list.borrow_mut().car {
-                        LitOrList::Identifier(list2) => {
-                            return NewIdentifierType::List(Rc::clone(list2));
-                        }
-                        LitOrList::Literal(var) => match var {
-                            LiteralOrFile::Literal(_) => {
-                                return NewIdentifierType::Vairable(NewVairable::new(var.clone()));
-                            }
-                            _ => error(line, "expected literal"),
-                        },
-                    }
-                }
-                match &list.borrow_mut().cdr {
-                    LitOrList::Identifier(list2) => {
-                        return NewIdentifierType::List(Rc::clone(&list2));
-                    }
-                    LitOrList::Literal(var) => match var {
-                        LiteralOrFile::Literal(_) => {
-                            return NewIdentifierType::Vairable(NewVairable::new(var.clone()));
-                        }
-                        _ => error(line, "expected literal"),
-                    },
-                }
-            }
-            error(line, "expected list, got something else");
+            let Value::Cons(cons) = self.get_var(&name[..name.len() - 4], span)? else {
+                return Err(error(span, "expected list, got something else"));
+            };
+            let cons = cons.borrow();
+            return Ok(if name.ends_with(".car") {
+                cons.car.clone()
+            } else {
+                cons.cdr.clone()
+            });
         }
-        match self.vars.get(name) {
-            Some(v) => match v {
-                NewIdentifierType::Vairable(_) => v.clone(),
-                NewIdentifierType::List(list) => NewIdentifierType::List(list.clone()),
-            },
-            None => self.parent_scope.as_mut().map_or_else(
-                || error(line, format!("variable not found {name}")),
-                |parent| parent.get_var(name, line),
-            ),
+        if let Some((path, var)) = module_path(name) {
+            let module_path: Vec<String> = path.split('$').map(ToString::to_string).collect();
+            let Some(module) = self.get_module(&module_path) else {
+                return Err(error(span, format!("module {path} is not loaded")));
+            };
+            return module.get_var(var, path, span);
         }
+        self.vars.get(name).map_or_else(
+            || {
+                self.parent_scope.as_ref().map_or_else(
+                    || Err(error(span, format!("variable not found {name}"))),
+                    |parent| parent.borrow().get_var(name, span),
+                )
+            },
+            |value| Ok(value.clone()),
+        )
     }
-    pub fn set_function(&mut self, name: char, body: Vec<Ast>, args: f64, extra: bool) {
-        self.function.insert(name, (body, args, extra));
+    pub fn set_function(&mut self, name: char, function: Potato) {
+        self.function.insert(name, Rc::new(function));
     }
-    pub fn get_function(&self, name: char, path: &[char]) -> Option<(Vec<Ast>, f64, bool)> {
-        if let Some(m) = path.first() {
-            // TODO: turn oprion into result b/c we could not find functions b/c module doesn't
-            // exist, not b/c function doesnt't exist needs differenent handler
-            Some(
-                self.open_modules[m.to_ascii_uppercase() as usize - 65]
-                    .as_ref()?
-                    .get_function(name, &path[1..])
-                    .clone(),
-            )
-        } else {
-            match self.function.get(&name) {
-                Some((body, args, extra)) => Some((body.clone(), *args, *extra)),
-                None => self
-                    .parent_scope
-                    .as_ref()
-                    .and_then(|parent| parent.get_function(name, path)),
-            }
+    pub fn get_function(&self, name: char, path: &[String]) -> Option<Rc<Potato>> {
+        if !path.is_empty() {
+            return self.get_module(path)?.get_function(name);
+        }
+        self.function.get(&name).cloned().or_else(|| {
+            self.parent_scope
+                .as_ref()
+                .and_then(|parent| parent.borrow().get_function(name, path))
+        })
+    }
+    /// finds the module at `path`, modules opened in outer scopes are visible in inner ones
+    pub fn get_module(&self, path: &[String]) -> Option<Rc<Module>> {
+        let (first, rest) = path.split_first()?;
+        match self.open_modules.get(first) {
+            Some(module) => rest.iter().try_fold(Rc::clone(module), |module, m| {
+                module.scope.borrow().open_modules.get(m).cloned()
+            }),
+            None => self.parent_scope.as_ref()?.borrow().get_module(path),
         }
     }
-    pub fn delete_var(&mut self, name: &str) -> Option<NewIdentifierType> {
+    pub fn delete_var(&mut self, name: &str) -> Option<Value> {
         self.vars.remove(name)
     }
     pub fn has_var(&self, name: &str, recurse: bool) -> bool {
-        let name: &str = if name.ends_with(".car") || name.ends_with(".cdr") {
-            &name[..name.len() - 4]
-        } else {
-            name
-        };
+        // x.cdr.car is in a scope if x is
+        let name = name.split('.').next().unwrap_or(name);
         if !recurse {
             return self.vars.contains_key(name);
         }
@@ -453,22 +426,9 @@ impl Scope {
         } else {
             self.parent_scope
                 .as_ref()
-                .map_or(false, |parent| parent.has_var(name, recurse))
+                .map_or(false, |parent| parent.borrow().has_var(name, recurse))
         }
     }
-    pub fn drop_scope(&mut self) {
-        let p_scope: Self = self
-            .parent_scope
-            .take()
-            .map_or_else(|| error(0, "no parent scope"), |scope| *scope);
-        *self = p_scope;
-    }
-
-    pub fn from_parent(&mut self) {
-        let mut temp_scope = Self::new();
-        swap(&mut temp_scope, self);
-        *self = Self::new_with_parent(Box::new(temp_scope));
-    }
     pub fn has_function(&self, name: char) -> bool {
         self.function.contains_key(&name)
     }
@@ -481,47 +441,139 @@ impl Default for Scope {
 }
 
 pub enum Stopper {
-    Break,
-    Continue,
-    Return(LiteralOrFile),
+    /// the label of the loop to break out of, or none for the innermost loop, and the value for the loop
+    Break(Option<String>, Value),
+    Continue(Option<String>),
+    Return(Value),
+}
+
+/// how a statement or block finished
+pub enum Flow {
+    /// it ran to the end, with its value
+    Done(Value),
+    /// it was left early by break, continue or return
+    Stop(Stopper),
+}
+
+/// a loop or function call that is being evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    Function,
+    Loop,
 }
 
 pub struct Eval {
-    pub scope: Scope,
-    pub in_function: bool,
-    pub in_loop: bool,
+    pub scope: Rc<RefCell<Scope>>,
+    /// the loops and function calls being evaluated, the innermost is last
+    pub contexts: Vec<Context>,
     pub files: HashMap<String, Rc<RefCell<File>>>,
+    /// the file being evaluated, modules it opens are looked for next to it
+    pub path: PathBuf,
+    pub modules: Rc<RefCell<Modules>>,
+}
+
+impl Default for Eval {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Eval {
     pub fn new() -> Self {
         Self {
-            scope: Scope::new(),
-            in_function: false,
-            in_loop: false,
+            scope: Rc::new(RefCell::new(Scope::new())),
+            contexts: Vec::new(),
             files: HashMap::new(),
+            path: PathBuf::new(),
+            modules: Rc::new(RefCell::new(Modules::new())),
         }
     }
 
-    pub fn eval_scope(&mut self, body: Vec<Ast>) -> Option<Stopper> {
-        let body = self.find_functions(body);
+    /// sets the file being evaluated, so it can open modules relative to itself
+    #[must_use]
+    pub fn with_file(mut self, path: &str) -> Self {
+        self.path = PathBuf::from(path);
+        let key = self
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| self.path.clone());
+        self.modules
+            .borrow_mut()
+            .loading
+            .push((key, path.to_string(), None));
+        self
+    }
+
+    /// adds directories to search for modules, before the ones from `UMPL_PATH`
+    #[must_use]
+    pub fn with_search_path(self, search_path: &[PathBuf]) -> Self {
+        self.modules
+            .borrow_mut()
+            .search_path
+            .splice(0..0, search_path.iter().cloned());
+        self
+    }
+
+    /// the warnings from parsing the modules loaded so far
+    pub fn take_warnings(&self) -> Vec<Diagnostic> {
+        mem::take(&mut self.modules.borrow_mut().warnings)
+    }
+
+    /// the source of every module that was loaded, by the name its spans use
+    pub fn take_sources(&self) -> HashMap<String, String> {
+        mem::take(&mut self.modules.borrow_mut().sources)
+    }
+
+    /// starts a new scope inside the current one
+    pub fn from_parent(&mut self) {
+        let parent = Rc::clone(&self.scope);
+        self.scope = Rc::new(RefCell::new(Scope::new_with_parent(parent)));
+    }
+
+    /// goes back to the scope the current one is in
+    pub fn drop_scope(&mut self) {
+        let parent = self.scope.borrow().parent_scope.clone();
+        self.scope = parent.expect("no parent scope");
+    }
+
+    pub fn eval_scope(&mut self, body: Vec<Ast>) -> Result<Option<Stopper>, UmplError> {
+        let body = self.find_functions(body)?;
         self.eval_expression(body)
     }
 
+    /// runs the top level of a file and gives back the value of its last statement
+    pub fn eval_program(&mut self, body: Vec<Ast>) -> Result<Value, UmplError> {
+        let body = self.find_functions(body)?;
+        match self.eval_block_value(body)? {
+            Flow::Done(value) | Flow::Stop(Stopper::Return(value)) => Ok(value),
+            Flow::Stop(_) => Ok(Value::Hempty),
+        }
+    }
+
     pub fn get_file(&self, name: &str) -> Option<RefMut<'_, File>> {
         self.files.get(name).map(|file| file.borrow_mut())
     }
 
-    pub fn find_functions(&mut self, body: Vec<Ast>) -> Vec<Ast> {
+    pub fn find_functions(&mut self, body: Vec<Ast>) -> Result<Vec<Ast>, UmplError> {
         let body = body
             .into_iter()
             .filter(|thing| -> bool {
                 if let Ast::Function(Function { node: function, .. }) = thing {
-                    self.scope.set_function(
-                        function.name.clone(),
-                        function.body.node.0.clone(),
-                        function.num_arguments,
-                        function.extra_arguments,
+                    if function.private {
+                        self.scope
+                            .borrow_mut()
+                            .private
+                            .insert(function.name.to_string());
+                    }
+                    self.scope.borrow_mut().set_function(
+                        function.name,
+                        Potato::new(
+                            Some(function.name),
+                            function.body.node.0.clone(),
+                            function.num_arguments,
+                            function.extra_arguments,
+                            &self.scope,
+                        ),
                     );
                     false
                 } else {
@@ -532,401 +584,735 @@ impl Eval {
         self.find_imports(body)
     }
 
-    // fn eval_module(&mut self) {
-    //     if args.len() != 2 {
-    //         error::error(line, format!("Expected 2 arguments for {self:?} operator"));
-    //     }
-    //     let module_name = match &args[0] {
-    //         LiteralNode::String(string) if string.is_ascii() && string.len() == 1 => string,
-    //         _ => error::error(line, format!("Expected string for {self:?} operator")),
-    //     };
-    //     match &args[1] {
-    //         LiteralNode::String(filename) => {
-    //             let prev_module_name = scope.module_name.clone();
-    //             if scope.module_name.is_empty() {
-    //                 scope.module_name = module_name.clone();
-    //             } else {
-    //                 scope.module_name = scope.module_name.clone() + "$" + module_name;
-    //             }
-    //             let _module_name = scope.module_name.clone();
-    //             let file = File::open(filename);
-    //             if let Ok(mut file) = file {
-    //                 let mut buf = String::new();
-    //                 if let Err(err) = file.read_to_string(&mut buf) {
-    //                     error::error(line, format!("Failed to read file: {err}"));
-    //                 }
-    //                 let lexer = Lexer::new(buf, filename.clone());
-    //                 let lexed = lexer.scan_tokens();
-    //                 let mut parsed = Parser::new(lexed, filename.clone());
-    //                 let body = parsed.parse();
-    //                 scope.find_functions(body);
-    //                 scope.module_name = prev_module_name;
-    //             } else {
-    //                 error::error(line, format!("Could not open file {filename:?}"));
-    //             };
-    //         }
-    //         _ => error::error(line, format!("Expected string for {self:?} operator")),
-    //     };
-    //
-    //     // see if there is the second argument is a string
-    //     LiteralNode::String(module_name.to_string())
-    // }
+    /// lexes, parses and evaluates the file at `path` in its own scope and opens it as module `name`
+    fn eval_module(&self, name: &str, path: &str, span: &Span) -> Result<(), UmplError> {
+        if !is_module_name(name) {
+            return Err(Diagnostic::error(
+                span,
+                format!("module name must be kebab-case, given {name}"),
+            )
+            .with_note("module names are lowercase letters, digits and dashes, like math-utils")
+            .into());
+        }
+        let module = self.load_module(path, span)?;
+        self.scope
+            .borrow_mut()
+            .open_modules
+            .insert(name.to_string(), module);
+        Ok(())
+    }
 
-    #[allow(clippy::too_many_lines)]
-    pub fn eval_expression(&mut self, body: Vec<Ast>) -> Option<Stopper> {
+    /// finds the function, a function missing from a module is its missing function handler
+    /// with the name given before the other arguments
+    fn get_function(
+        &self,
+        name: char,
+        path: &[String],
+        span: &Span,
+    ) -> Result<Rc<Potato>, UmplError> {
+        let function = self.scope.borrow().get_function(name, path);
+        function.map_or_else(|| self.missing_function(name, path, span), Ok)
+    }
+
+    /// finds the missing function handler of the module at `path` and binds `name` as its first argument
+    fn missing_function(
+        &self,
+        name: char,
+        path: &[String],
+        span: &Span,
+    ) -> Result<Rc<Potato>, UmplError> {
+        if path.is_empty() {
+            return Err(error(span, format!("Function {name} is not defined")));
+        }
+        let module_name = path.join("$");
+        let Some(module) = self.scope.borrow().get_module(path) else {
+            return Err(
+                Diagnostic::error(span, format!("module {module_name} is not loaded"))
+                    .with_note("modules are opened with ((module `name` `file.umpl`))<")
+                    .into(),
+            );
+        };
+        if module.is_private(&name.to_string()) {
+            return Err(error(
+                span,
+                format!("function {name} is private to module {module_name}"),
+            ));
+        }
+        let Some(handler) = module.missing_function_handler.clone() else {
+            return Err(Diagnostic::error(
+                span,
+                format!("function {name} not found in module {module_name}"),
+            )
+            .with_note(format!(
+                "a module can handle missing functions with potato {MISSING_FUNCTION_HANDLER} 1* ⧼...⧽"
+            ))
+            .into());
+        };
+        let mut handler = (*handler).clone();
+        handler
+            .bound_arguments
+            .insert(0, Value::String(name.to_string()));
+        Ok(Rc::new(handler))
+    }
+
+    /// the function as a value, the scope it keeps alive is emptied when the program ends
+    fn function_value(&self, function: &Rc<Potato>) -> Value {
+        self.modules.borrow_mut().keep_alive(&function.scope);
+        function.to_value()
+    }
+
+    /// calls the function in a new scope with the arguments as $1, $2 and so on
+    fn call_function(
+        &mut self,
+        function: &Potato,
+        args: Vec<Value>,
+        span: &Span,
+    ) -> Result<Value, UmplError> {
+        let args: Vec<Value> = function
+            .bound_arguments
+            .iter()
+            .cloned()
+            .chain(args)
+            .collect();
+        arg_error(
+            function.num_arguments as u32,
+            args.len() as u32,
+            function,
+            function.extra_arguments,
+            span,
+        )?;
+        // functions run inside the scope they were declared in, so they cannot see the caller's variables
+        let caller = mem::replace(
+            &mut self.scope,
+            Rc::new(RefCell::new(Scope::new_with_parent(function.scope()))),
+        );
+        self.contexts.push(Context::Function);
+        let result = self.eval_function_body(function, args, span);
+        self.contexts.pop();
+        self.scope = caller;
+        match result? {
+            Some(Stopper::Return(a)) => Ok(a),
+            Some(_) => Err(error(span, "cannot call break/continue at end of function")),
+            None => Ok(Value::Hempty),
+        }
+    }
+
+    fn eval_function_body(
+        &mut self,
+        function: &Potato,
+        mut args: Vec<Value>,
+        span: &Span,
+    ) -> Result<Option<Stopper>, UmplError> {
+        let body = self.find_functions(function.body.clone())?;
+        // the arguments past the ones specified in the function are put in one list variable $n
+        let extra_args = args.split_off(function.num_arguments as usize);
+        for (i, l) in args.into_iter().enumerate() {
+            self.scope
+                .borrow_mut()
+                .set_var(format!("${}", i + 1).as_str(), l, false, span)?;
+        }
+        if !extra_args.is_empty() {
+            self.scope.borrow_mut().set_var(
+                format!("${}", function.num_arguments as usize + 1).as_str(),
+                Value::list(extra_args),
+                false,
+                span,
+            )?;
+        }
+        self.eval_expression(body)
+    }
+
+    fn load_module(&self, path: &str, span: &Span) -> Result<Rc<Module>, UmplError> {
+        let resolved = self
+            .modules
+            .borrow()
+            .resolve(&self.path, path)
+            .ok_or_else(|| {
+                Diagnostic::error(span, format!("could not find module {path}")).with_note(
+                    "modules are looked for next to the importing file, then in the -I directories, then in UMPL_PATH, then in the current directory",
+                )
+            })?;
+        let name = resolved.display().to_string();
+        let key = resolved.canonicalize().unwrap_or_else(|_| resolved.clone());
+        {
+            let modules = self.modules.borrow();
+            if let Some(module) = modules.loaded.get(&key) {
+                return Ok(Rc::clone(module));
+            }
+            if let Some(start) = modules.loading.iter().position(|(path, ..)| path == &key) {
+                return Err(modules.cycle_error(start, &name, span));
+            }
+        }
+        let source = read_file(&name)
+            .map_err(|err| error(span, format!("Could not load module {name}: {err}")))?;
+        self.modules
+            .borrow_mut()
+            .sources
+            .insert(name.clone(), source.clone());
+        let tokens = Lexer::new(source, name.clone()).scan_tokens()?;
+        let mut parser = Parser::new(tokens, name.clone());
+        let body = parser.parse();
+        self.modules
+            .borrow_mut()
+            .warnings
+            .extend(parser.take_warnings());
+        let body = body?;
+        self.modules
+            .borrow_mut()
+            .loading
+            .push((key.clone(), name, Some(span.clone())));
+        let mut module = Self {
+            path: resolved,
+            modules: Rc::clone(&self.modules),
+            ..Self::new()
+        };
+        let result = module.eval_scope(body);
+        self.modules.borrow_mut().loading.pop();
+        result?;
+        let handler = module
+            .scope
+            .borrow()
+            .get_function(MISSING_FUNCTION_HANDLER, &[]);
+        let mut module = Module::new(module.scope);
+        module.set_missing_function_handler(handler);
+        let module = Rc::new(module);
+        self.modules
+            .borrow_mut()
+            .loaded
+            .insert(key, Rc::clone(&module));
+        Ok(module)
+    }
+
+    pub fn eval_expression(&mut self, body: Vec<Ast>) -> Result<Option<Stopper>, UmplError> {
         // create a vector to return instead of inplace modification
         // well have globa/local scope when we check for variables we check for variables in the current scope and then check the parent scope and so on until we find a variable or we reach the top of the scope stack (same for functions)
         // we can have two different variables with the same name in different scopes, the scope of a variable is determined by where it is declared in the code
         debug!("find variables in scope");
         // print variables in scope
-        for (name, var) in &self.scope.vars {
-            debug!("{}: {:?}", name, var);
+        for (name, var) in &self.scope.borrow().vars {
+            debug!("{name}: {var:?}");
         }
         for thing in body {
-            match thing {
-                Ast::Declaration(Declaration {
-                    node: ref variable,
-                    start_line: line,
-                    ..
-                }) => match variable.value {
-                    DeclarationType::Variable(ref name) => {
-                        if let Some(pointer) = self.find_pointer_in_other_stuff(&name) {
-                            self.scope
-                                .set_var(&variable.name, &mut vec![pointer], false, line);
-                        } else {
-                            self.scope.set_var(
-                                &variable.name,
-                                &mut vec![LiteralOrFile::Literal(LiteralNode::get_from_ast(
-                                    &name, line,
-                                ))],
-                                false,
-                                line,
-                            );
-                        }
-                    }
-                    DeclarationType::Cons(ref list) => {
-                        let car: LiteralOrFile =
-                            self.find_pointer_in_other_stuff(&list.car).map_or_else(
-                                || {
-                                    LiteralOrFile::Literal(LiteralNode::get_from_ast(
-                                        &list.car, line,
-                                    ))
-                                },
-                                |pointer| pointer,
-                            );
-                        let cdr: LiteralOrFile =
-                            self.find_pointer_in_other_stuff(&list.cdr).map_or_else(
-                                || {
-                                    LiteralOrFile::Literal(LiteralNode::get_from_ast(
-                                        &list.cdr, line,
-                                    ))
-                                },
-                                |pointer| pointer,
-                            );
-                        self.scope
-                            .set_var(&variable.name, &mut vec![car, cdr], false, line);
-                    }
-                },
-                Ast::Return(Return {
-                    node,
-                    start_line: line,
-                    ..
-                }) => {
-                    let ret: LiteralOrFile =
-                        node.0
-                            .map_or(LiteralOrFile::Literal(LiteralNode::Hempty), |os| {
-                                self.find_pointer_in_other_stuff(&os).map_or_else(
-                                    || LiteralOrFile::Literal(LiteralNode::get_from_ast(&os, line)),
-                                    |identifier| identifier,
-                                )
-                            });
-                    return Some(Stopper::Return(ret));
+            if let Flow::Stop(stopper) = self.eval_statement(thing)? {
+                return Ok(Some(stopper));
+            }
+        }
+        Ok(None)
+    }
+
+    /// runs the statements and gives back the value of the last one
+    fn eval_block_value(&mut self, body: Vec<Ast>) -> Result<Flow, UmplError> {
+        let mut value = Value::Hempty;
+        for thing in body {
+            match self.eval_statement(thing)? {
+                Flow::Done(done) => value = done,
+                stop @ Flow::Stop(_) => return Ok(stop),
+            }
+        }
+        Ok(Flow::Done(value))
+    }
+
+    /// runs the code in a new scope
+    fn eval_block(&mut self, body: Vec<Ast>) -> Result<Flow, UmplError> {
+        self.from_parent();
+        let body = self.find_functions(body)?;
+        let flow = self.eval_block_value(body)?;
+        self.drop_scope();
+        Ok(flow)
+    }
+
+    /// runs the code in a new scope that already has the variables in it
+    fn eval_block_with(
+        &mut self,
+        body: Vec<Ast>,
+        vars: Vec<(String, Value)>,
+        span: &Span,
+    ) -> Result<Flow, UmplError> {
+        self.from_parent();
+        let flow = vars
+            .into_iter()
+            .try_for_each(|(name, value)| {
+                self.scope.borrow_mut().set_var(&name, value, false, span)
+            })
+            .and_then(|()| self.find_functions(body))
+            .and_then(|body| self.eval_block_value(body));
+        self.drop_scope();
+        flow
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn eval_statement(&mut self, thing: Ast) -> Result<Flow, UmplError> {
+        Ok(match thing {
+            Ast::Declaration(Declaration {
+                node: ref variable,
+                ref print,
+                ref span,
+            }) => {
+                let value = self.declare(variable, span)?;
+                print_value(&value, print, span)?;
+                Flow::Done(value)
+            }
+            Ast::Return(Return { node, .. }) => {
+                let ret = match node.0 {
+                    Some(os) => self.find_pointer_in_stuff(&os)?,
+                    None => Value::Hempty,
+                };
+                Flow::Stop(Stopper::Return(ret))
+            }
+            Ast::Call(Call {
+                ref print,
+                ref span,
+                ..
+            })
+            | Ast::Literal(Literal {
+                ref print,
+                ref span,
+                ..
+            })
+            | Ast::Identifier(Identifier {
+                ref print,
+                ref span,
+                ..
+            })
+            | Ast::List(List {
+                ref print,
+                ref span,
+                ..
+            })
+            | Ast::FunctionIdentifier(FunctionIdentifier {
+                ref print,
+                ref span,
+                ..
+            })
+            | Ast::Lambda(Lambda {
+                ref print,
+                ref span,
+                ..
+            }) => {
+                let value = self.find_pointer_in_stuff(&thing)?;
+                print_value(&value, print, span)?;
+                Flow::Done(value)
+            }
+            Ast::Block(Block {
+                node: block,
+                ref print,
+                ref span,
+            }) => {
+                let flow = self.eval_block(block.0)?;
+                if let Flow::Done(ref value) = flow {
+                    print_value(value, print, span)?;
                 }
-                // TODO: print exrpessions
-                // Ast::Expression(expr) => {
-                //     let exprs = self.find_pointer_in_stuff(&expr.inside);
-                //     print!(
-                //         "{}",
-                //         NewExpression {
-                //             inside: exprs,
-                //             print: expr.print,
-                //             line: expr.start_line,
-                //             new_line: expr.new_line,
-                //         }
-                //     );
-                // }
-                Ast::If(If {
-                    node: mut if_statement,
-                    start_line,
-                    ..
-                }) => {
-                    let conditon: LiteralNode =
-                        match self.find_pointer_in_other_stuff(&if_statement.condition) {
-                            Some(pointer) => {
-                                info!("if {:?}", pointer);
-                                match pointer {
-                                    LiteralOrFile::Literal(literal) => literal,
-                                    _ => error(start_line, "cannot compare files"),
-                                }
-                            }
-                            None => LiteralNode::get_from_ast(&if_statement.condition, start_line),
-                        };
-                    if conditon.type_eq(&LiteralNode::Boolean(true)) {
-                    } else {
-                        error(start_line, "expected boolean, got something else");
-                    }
-                    self.scope.from_parent();
-                    if conditon == LiteralNode::Boolean(true) {
-                        if_statement.body_true.node.0 =
-                            self.find_functions(if_statement.body_true.node.0);
-                        let body_true: Option<Stopper> =
-                            self.eval_expression(if_statement.body_true.node.0);
-                        self.scope.drop_scope();
-                        if let Some(stop) = body_true {
-                            match stop {
-                                Stopper::Break | Stopper::Continue => {
-                                    if self.in_loop {
-                                        return Some(stop);
-                                    }
-                                    error(start_line, "break or continue outside of loop");
-                                }
-                                Stopper::Return(ret) => {
-                                    if self.in_function {
-                                        return Some(Stopper::Return(ret));
-                                    }
-                                    error(start_line, "return outside of function");
-                                }
-                            }
-                        }
-                    } else {
-                        if_statement.body_false.node.0 =
-                            self.find_functions(if_statement.body_false.node.0);
-                        let z = self.eval_expression(if_statement.body_false.node.0);
-                        self.scope.drop_scope();
-                        if let Some(stop) = z {
-                            if let Stopper::Return(ret) = stop {
-                                if self.in_function {
-                                    return Some(Stopper::Return(ret));
-                                }
-                                error(start_line, "return outside of function");
-                            } else {
-                                if self.in_loop {
-                                    return Some(stop);
-                                }
-                                error(start_line, "break or continue outside of loop");
-                            }
-                        }
-                    }
+                flow
+            }
+            Ast::If(If {
+                node: if_statement,
+                ref print,
+                ref span,
+            }) => {
+                // only the body that runs gets a scope, not every else if before it
+                let flow = match self.if_body(if_statement)? {
+                    Some(body) => self.eval_block(body)?,
+                    None => Flow::Done(Value::Hempty),
+                };
+                self.finish_branch(&flow, print, span)?;
+                flow
+            }
+            Ast::Match(Match {
+                node: match_statement,
+                ref print,
+                ref span,
+            }) => {
+                let flow = self.eval_match(match_statement, span)?;
+                self.finish_branch(&flow, print, span)?;
+                flow
+            }
+            Ast::Try(Try {
+                node: try_statement,
+                ref print,
+                ref span,
+            }) => {
+                let flow = self.eval_try(try_statement, span)?;
+                self.finish_branch(&flow, print, span)?;
+                flow
+            }
+            Ast::Loop(ref loop_statement) => {
+                self.contexts.push(Context::Loop);
+                let flow = self.eval_loop(loop_statement);
+                self.contexts.pop();
+                let flow = flow?;
+                if let Flow::Done(ref value) = flow {
+                    print_value(value, &loop_statement.print, &loop_statement.span)?;
                 }
-                Ast::Loop(loop_statement) => {
-                    'l: loop {
-                        self.scope.from_parent();
-                        let loop_body = self.find_functions(loop_statement.node.0.node.0.clone());
-                        self.in_loop = true;
-                        let z: Option<Stopper> = self.eval_expression(loop_body.clone());
-                        self.scope.drop_scope();
-                        if let Some(stop) = z {
-                            match stop {
-                                Stopper::Break => break 'l,
-                                Stopper::Continue => continue 'l,
-                                Stopper::Return(ret) => {
-                                    if self.in_function {
-                                        return Some(Stopper::Return(ret));
-                                    }
-                                    error(loop_statement.start_line, "return outside of function");
-                                }
-                            }
-                        }
-                    }
-                    self.in_loop = false;
+                flow
+            }
+            Ast::Break(Break { node, .. }) => {
+                let value = match node.1 {
+                    Some(value) => self.find_pointer_in_stuff(&value)?,
+                    None => Value::Hempty,
+                };
+                Flow::Stop(Stopper::Break(node.0, value))
+            }
+            Ast::Continue(Continue { node, .. }) => Flow::Stop(Stopper::Continue(node.0)),
+            Ast::Function(_) => Flow::Done(Value::Hempty),
+        })
+    }
+
+    /// sets the variable in the current scope and gives back its value
+    fn declare(&mut self, variable: &DeclarationNode, span: &Span) -> Result<Value, UmplError> {
+        let value = self.find_pointer_in_stuff(&variable.value)?;
+        self.scope
+            .borrow_mut()
+            .set_var(&variable.name, value.clone(), false, span)?;
+        if variable.private {
+            self.scope
+                .borrow_mut()
+                .private
+                .insert(variable.name.clone());
+        }
+        Ok(value)
+    }
+
+    /// the body of the first branch of the if with a true condition, or the else if there is one
+    fn if_body(&mut self, if_statement: IfNode) -> Result<Option<Vec<Ast>>, UmplError> {
+        for branch in if_statement.branches {
+            let condition = self.find_pointer_in_stuff(&branch.condition)?;
+            info!("if {condition:?}");
+            let Value::Boolean(condition) = condition else {
+                return Err(error(
+                    branch.condition.span(),
+                    "expected boolean, got something else",
+                ));
+            };
+            if condition {
+                return Ok(Some(branch.body.node.0));
+            }
+        }
+        Ok(if_statement.body_false.map(|body| body.node.0))
+    }
+
+    /// prints the value of an if, match or try, a break, continue or return from its body
+    /// has to have somewhere to go
+    fn finish_branch(&self, flow: &Flow, print: &PrintType, span: &Span) -> Result<(), UmplError> {
+        match flow {
+            Flow::Done(value) => print_value(value, print, span)?,
+            Flow::Stop(Stopper::Return(_)) if !self.in_function() => {
+                return Err(error(span, "return outside of function"));
+            }
+            Flow::Stop(Stopper::Break(..) | Stopper::Continue(_)) if !self.in_loop() => {
+                return Err(error(span, "break or continue outside of loop"));
+            }
+            Flow::Stop(_) => {}
+        }
+        Ok(())
+    }
+
+    /// runs the body of the first arm whose pattern fits the value, or the else if there is one,
+    /// the names a pair pattern gives to the car and cdr only exist in the body of its arm
+    fn eval_match(&mut self, match_statement: MatchNode, span: &Span) -> Result<Flow, UmplError> {
+        let value = self.find_pointer_in_stuff(&match_statement.value)?;
+        info!("match {value:?}");
+        let mut arm = None;
+        for MatchArm { pattern, body } in match_statement.arms {
+            let names = match (pattern, &value) {
+                (Pattern::Literal(literal), _) if Value::from(literal.clone()) == value => vec![],
+                (Pattern::Type(name), _) if name == value.get_type() => vec![],
+                (Pattern::Cons { car, cdr }, Value::Cons(cons)) => {
+                    let cons = cons.borrow();
+                    vec![(car, cons.car.clone()), (cdr, cons.cdr.clone())]
                 }
-                Ast::Break(..) => {
-                    return Some(Stopper::Break);
+                _ => continue,
+            };
+            arm = Some((body, names));
+            break;
+        }
+        match arm.or_else(|| match_statement.default.map(|body| (body, Vec::new()))) {
+            Some((body, names)) => self.eval_block_with(body.node.0, names, span),
+            None => Ok(Flow::Done(Value::Hempty)),
+        }
+    }
+
+    /// runs the body of the try, if it errors the rescue runs instead with a list of the message
+    /// and line of the error in its variable, an exit is not an error so it cannot be rescued
+    fn eval_try(&mut self, try_statement: TryNode, span: &Span) -> Result<Flow, UmplError> {
+        // the error could have come from any number of scopes and calls deep, so they are put back
+        let scope = Rc::clone(&self.scope);
+        let contexts = self.contexts.len();
+        let diagnostic = match self.eval_block(try_statement.body.node.0) {
+            Err(UmplError::Diagnostic(diagnostic)) => diagnostic,
+            Err(UmplError::Diagnostics(mut diagnostics)) if !diagnostics.is_empty() => {
+                diagnostics.swap_remove(0)
+            }
+            result => return result,
+        };
+        info!("rescued {diagnostic}");
+        self.scope = scope;
+        self.contexts.truncate(contexts);
+        let line = diagnostic.span().map_or(Value::Hempty, |span| {
+            Value::Number(f64::from(span.start_line))
+        });
+        let error = Value::list(vec![Value::String(diagnostic.message), line]);
+        self.eval_block_with(
+            try_statement.rescue.node.0,
+            vec![(try_statement.name, error)],
+            span,
+        )
+    }
+
+    /// runs the body of the loop until it breaks, the loop's value is the value given to break,
+    /// a return is passed on to the function the loop is in
+    fn eval_loop(&mut self, loop_statement: &Loop) -> Result<Flow, UmplError> {
+        let span = &loop_statement.span;
+        let mut over = match &loop_statement.node.over {
+            Some(over) => Some((over.name.as_str(), self.loop_items(&over.items)?)),
+            None => None,
+        };
+        let label = &loop_statement.node.label;
+        // a break or continue for a loop further out ends this loop and is passed on
+        let is_this_loop = |target: &Option<String>| target.is_none() || target == label;
+        let flow = loop {
+            self.from_parent();
+            if let Some((name, items)) = &mut over {
+                let Some(item) = items.next() else {
+                    self.drop_scope();
+                    break Flow::Done(Value::Hempty);
+                };
+                self.scope.borrow_mut().set_var(name, item, false, span)?;
+            }
+            // the functions are declared in every iteration's scope so they see its variables
+            let loop_body = self.find_functions(loop_statement.node.body.node.0.clone())?;
+            let stopper = self.eval_expression(loop_body)?;
+            self.drop_scope();
+            match stopper {
+                Some(Stopper::Break(target, value)) if is_this_loop(&target) => {
+                    break Flow::Done(value)
                 }
-                Ast::Continue(..) => {
-                    return Some(Stopper::Continue);
+                Some(Stopper::Continue(target)) if is_this_loop(&target) => {}
+                None => {}
+                Some(Stopper::Return(_)) if !self.in_function() => {
+                    return Err(error(span, "return outside of function"));
                 }
-                _ => {}
+                Some(stopper) => break Flow::Stop(stopper),
             }
-        }
-        None
+        };
+        Ok(flow)
     }
 
-    pub fn find_imports(&mut self, body: Vec<Ast>) -> Vec<Ast> {
-        body.into_iter()
-            .filter(|thing| match thing {
-                // Ast::Expression(e) => match &e.inside {
-                Ast::Call(call) => match call.node.keyword {
-                    TokenType::Module => {
-                        let mut new_stuff: Vec<LiteralNode> = Vec::new();
-                        call.node.arguments.iter().for_each(|thing| {
-                            match self.find_pointer_in_stuff(thing) {
-                                LiteralOrFile::Literal(literal) => {
-                                    new_stuff.push(literal);
-                                }
-                                LiteralOrFile::File(_) => {
-                                    error(
-                                        call.start_line,
-                                        format!(
-                                            "Cannot use file as argument for function {}",
-                                            call.node.keyword
-                                        )
-                                        .as_str(),
-                                    );
-                                }
-                            }
-                        });
-                        TokenType::Module.r#do(&new_stuff, call.start_line);
-                        false
-                    }
-                    _ => true,
-                },
-                _ => true,
-                // },
-                // _ => true,
-            })
-            .collect()
+    /// the items a loop over goes through, a range makes each number when it is needed instead of all at once
+    fn loop_items(&mut self, items: &Ast) -> Result<Box<dyn Iterator<Item = Value>>, UmplError> {
+        let span = items.span();
+        Ok(match self.find_pointer_in_stuff(items)? {
+            Value::Range(range) => Box::new(range.map(Value::Number)),
+            list @ (Value::Cons(_) | Value::Hempty) => {
+                Box::new(list.to_vec(span, &TokenType::Over)?.into_iter())
+            }
+            value => {
+                return Err(error(
+                    span,
+                    format!(
+                        "loop over requires a list or range, found a {}",
+                        value.get_type()
+                    ),
+                ))
+            }
+        })
+    }
+
+    fn in_function(&self) -> bool {
+        self.contexts.contains(&Context::Function)
     }
 
-    fn find_pointer_in_other_stuff(&mut self, other_stuff: &Ast) -> Option<LiteralOrFile> {
-        match other_stuff {
-            Ast::Identifier(ident) => match self.scope.get_var(&ident.node.0, ident.start_line) {
-                NewIdentifierType::List(..) => {
-                    error(ident.start_line, "whole list not supported in call")
+    /// break and continue go to the innermost loop, but not past a function call
+    fn in_loop(&self) -> bool {
+        self.contexts.last() == Some(&Context::Loop)
+    }
+
+    pub fn find_imports(&mut self, body: Vec<Ast>) -> Result<Vec<Ast>, UmplError> {
+        let mut new_body = Vec::new();
+        for thing in body {
+            match thing {
+                Ast::Call(ref call) if call.node.keyword == TokenType::Module => {
+                    self.find_pointer_in_stuff(&thing)?;
                 }
-                NewIdentifierType::Vairable(var) => match var.value {
-                    LiteralOrFile::Literal(_) => Some(var.value),
-                    _ => error(ident.start_line, "variable is not a literal"),
-                },
-            },
-            expr => Some(self.find_pointer_in_stuff(&expr)),
-            // _ => None,
+                thing => new_body.push(thing),
+            }
         }
+        Ok(new_body)
     }
+
     #[allow(clippy::too_many_lines)]
-    fn find_pointer_in_stuff(&mut self, stuff: &Ast) -> LiteralOrFile {
+    fn find_pointer_in_stuff(&mut self, stuff: &Ast) -> Result<Value, UmplError> {
         // need to make ways to extract values from literaltypes/literal/vars easy with function
-        match stuff {
-            Ast::Identifier(ident) => match self.scope.get_var(&ident.node.0, ident.start_line) {
-                NewIdentifierType::List(..) => {
-                    error(ident.start_line, "whole list not supported in call")
-                }
-                NewIdentifierType::Vairable(var) => var.value,
-            },
+        Ok(match stuff {
+            Ast::Identifier(ident) => self.scope.borrow().get_var(&ident.node.0, &ident.span)?,
             Ast::Call(Call {
-                node: call,
-                start_line,
-                ..
+                node: call, span, ..
             }) => {
-                let start_line = *start_line;
                 match &call.keyword {
                     TokenType::FunctionIdentifier { name, path } => {
-                        if let Some(mut function) = self.scope.get_function(*name, path) {
-                            let new_stuff: Vec<LiteralOrFile> = call
-                                .arguments
-                                .iter()
-                                .map(|thing| self.find_pointer_in_stuff(thing))
-                                .collect();
-                            arg_error(
-                                function.1 as u32,
-                                new_stuff.len() as u32,
-                                &call.keyword,
-                                function.2,
-                                start_line,
-                            );
-                            self.scope.from_parent();
-                            function.0 = self.find_functions(function.0);
-                            self.in_function = true;
-                            let mut extra_args: Option<NewList> = None;
-
-                            // TODO: once we have more than ammount of arguments specified in function we should label the rest as under one variable $n which is a list
-                            new_stuff.into_iter().enumerate().for_each(|(i, l)| {
-                                if i as f64 >= function.1 {
-                                    if let Some(ref mut list) = extra_args {
-                                        list.set_last(LitOrList::Identifier(Rc::new(
-                                            RefCell::new(NewList {
-                                                car: LitOrList::Literal(l),
-                                                cdr: LitOrList::Literal(LiteralOrFile::Literal(
-                                                    LiteralNode::Hempty,
-                                                )),
-                                            }),
-                                        )));
-                                    } else {
-                                        extra_args = Some(NewList {
-                                            car: LitOrList::Literal(l),
-                                            cdr: LitOrList::Literal(LiteralOrFile::Literal(
-                                                LiteralNode::Hempty,
-                                            )),
-                                        });
+                        let new_stuff: Vec<Value> = call
+                            .arguments
+                            .iter()
+                            .map(|thing| self.find_pointer_in_stuff(thing))
+                            .collect::<Result<_, _>>()?;
+                        let function = self.get_function(*name, path, span)?;
+                        self.call_function(&function, new_stuff, span)?
+                    }
+                    TokenType::Identifier { name } => {
+                        let value = self.scope.borrow().get_var(name, span)?;
+                        let function = match value {
+                            Value::Function(function) => function,
+                            value => {
+                                return Err(error(
+                                    span,
+                                    format!(
+                                        "{name} is not a function, it is a {}",
+                                        value.get_type()
+                                    ),
+                                ))
+                            }
+                        };
+                        let args = call
+                            .arguments
+                            .iter()
+                            .map(|thing| self.find_pointer_in_stuff(thing))
+                            .collect::<Result<_, _>>()?;
+                        self.call_function(&function, args, span)?
+                    }
+                    TokenType::Map | TokenType::Filter | TokenType::SortBy => {
+                        arg_error(2, call.arguments.len() as u32, &call.keyword, false, span)?;
+                        let function = self
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_function(span, &call.keyword)?;
+                        let items = self
+                            .find_pointer_in_stuff(&call.arguments[1])?
+                            .to_vec(span, &call.keyword)?;
+                        match call.keyword {
+                            TokenType::Map => Value::list(
+                                items
+                                    .into_iter()
+                                    .map(|item| self.call_function(&function, vec![item], span))
+                                    .collect::<Result<_, _>>()?,
+                            ),
+                            TokenType::Filter => {
+                                let mut kept = Vec::new();
+                                for item in items {
+                                    match self.call_function(&function, vec![item.clone()], span)? {
+                                        Value::Boolean(true) => kept.push(item),
+                                        Value::Boolean(false) => {}
+                                        other => {
+                                            return Err(error(
+                                                span,
+                                                format!(
+                                                    "{function} must return a boolean for {}, returned {}",
+                                                    call.keyword,
+                                                    other.get_type()
+                                                ),
+                                            ))
+                                        }
                                     }
-                                } else {
-                                    self.scope.set_var(
-                                        format!("${}", i + 1).as_str(),
-                                        &mut vec![(l)],
-                                        false,
-                                        start_line,
-                                    );
                                 }
-                            });
-                            if let Some(list) = extra_args {
-                                self.scope.set_list(
-                                    format!("${}", function.1 as usize + 1).as_str(),
-                                    list,
-                                    false,
-                                    start_line,
-                                );
+                                Value::list(kept)
                             }
-                            let z: Option<Stopper> = self.eval_expression(function.0);
-                            self.in_function = false;
-                            self.scope.drop_scope();
-                            z.map_or(LiteralOrFile::Literal(LiteralNode::Hempty), |v| {
-                                if let Stopper::Return(a) = v {
-                                    a
-                                } else {
-                                    error(
-                                        start_line,
-                                        "cannot call break/continue at end of function",
-                                    );
+                            _ => {
+                                // sorts by the key the function gives each item, keys are all numbers or all strings
+                                let mut keyed = items
+                                    .into_iter()
+                                    .map(|item| {
+                                        Ok((
+                                            self.call_function(
+                                                &function,
+                                                vec![item.clone()],
+                                                span,
+                                            )?,
+                                            item,
+                                        ))
+                                    })
+                                    .collect::<Result<Vec<_>, UmplError>>()?;
+                                let mut mismatch = None;
+                                keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+                                    (Value::Number(a), Value::Number(b)) => a.total_cmp(b),
+                                    (Value::String(a), Value::String(b)) => a.cmp(b),
+                                    _ => {
+                                        mismatch
+                                            .get_or_insert_with(|| (a.get_type(), b.get_type()));
+                                        Ordering::Equal
+                                    }
+                                });
+                                if let Some((a, b)) = mismatch {
+                                    return Err(error(
+                                        span,
+                                        format!("{} can only compare numbers with numbers or strings with strings, found {a} and {b}", call.keyword),
+                                    ));
                                 }
-                            })
-                        } else {
-                            error(start_line, format!("Function {name} is not defined"));
+                                Value::list(keyed.into_iter().map(|(_, item)| item).collect())
+                            }
                         }
                     }
-                    TokenType::Type => {
+                    TokenType::Reduce | TokenType::Fold => {
+                        // fold starts with the value given, reduce with the first item
+                        let arguments = if call.keyword == TokenType::Fold {
+                            3
+                        } else {
+                            2
+                        };
                         arg_error(
-                            1,
+                            arguments,
                             call.arguments.len() as u32,
                             &call.keyword,
                             false,
-                            start_line,
-                        );
-                        match self.find_pointer_in_stuff(&call.arguments[0]) {
-                            LiteralOrFile::Literal(a) => {
-                                LiteralOrFile::Literal(LiteralNode::String(a.get_type()))
-                            }
-                            LiteralOrFile::File(_) => {
-                                LiteralOrFile::Literal(LiteralNode::String("file".to_string()))
-                            }
-                        }
+                            span,
+                        )?;
+                        let function = self
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_function(span, &call.keyword)?;
+                        let mut items = self
+                            .find_pointer_in_stuff(&call.arguments[arguments as usize - 1])?
+                            .to_vec(span, &call.keyword)?
+                            .into_iter();
+                        let initial = if call.keyword == TokenType::Fold {
+                            self.find_pointer_in_stuff(&call.arguments[1])?
+                        } else {
+                            items.next().ok_or_else(|| {
+                                error(span, format!("{} requires a non empty list", call.keyword))
+                            })?
+                        };
+                        items.try_fold(initial, |accumulator, item| {
+                            self.call_function(&function, vec![accumulator, item], span)
+                        })?
+                    }
+                    TokenType::Module => {
+                        arg_error(2, call.arguments.len() as u32, &call.keyword, false, span)?;
+                        let name = self
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_string(span, &call.keyword)?;
+                        let path = self
+                            .find_pointer_in_stuff(&call.arguments[1])?
+                            .get_string(span, &call.keyword)?;
+                        self.eval_module(&name, &path, span)?;
+                        Value::String(name)
+                    }
+                    TokenType::Type => {
+                        arg_error(1, call.arguments.len() as u32, &call.keyword, false, span)?;
+                        Value::String(self.find_pointer_in_stuff(&call.arguments[0])?.get_type())
                     }
                     TokenType::Delete => {
                         if call.arguments.len() != 1 {
-                            error(start_line, "delete takes one argument");
+                            return Err(error(span, "delete takes one argument"));
                         }
                         if let Ast::Identifier(Identifier {
-                            node: ident,
-                            start_line,
-                            ..
+                            node: ident, span, ..
                         }) = &call.arguments[0]
                         {
-                            if self.scope.delete_var(&ident.0).is_some() {
-                                LiteralOrFile::Literal(LiteralNode::Hempty)
+                            if self.scope.borrow_mut().delete_var(&ident.0).is_some() {
+                                Value::Hempty
                             } else {
-                                error(*start_line, format!("Variable {} is not defined", ident.0));
+                                return Err(error(
+                                    span,
+                                    format!("Variable {} is not defined", ident.0),
+                                ));
                             }
                         } else {
-                            error(start_line, "delete only takes a variable name")
+                            return Err(error(span, "delete only takes a variable name"));
                         }
                     }
                     TokenType::AddWith
@@ -935,562 +1321,206 @@ impl Eval {
                     | TokenType::MultiplyWith
                     | TokenType::Set => {
                         debug!("{} {:?}", call.keyword, call.arguments);
+                        arg_error(2, call.arguments.len() as u32, &call.keyword, true, span)?;
                         if let Ast::Identifier(Identifier {
                             node: ident,
-                            start_line: ident_line,
+                            span: ident_span,
                             ..
                         }) = &call.arguments[0]
                         {
-                            if self.scope.has_var(&ident.0, true) {
-                                let mut new_stuff: Vec<LiteralOrFile> = Vec::new();
-                                call.arguments.iter().skip(1).for_each(|thing| {
-                                    new_stuff.push(self.find_pointer_in_stuff(thing));
-                                });
-                                match new_stuff.len() {
-                                    1 => {
-                                        let literal: LiteralOrFile = new_stuff.remove(0);
-                                        let var: LiteralOrFile =
-                                            match self.scope.get_var(&ident.0, start_line) {
-                                                NewIdentifierType::Vairable(v) => v.value,
-                                                NewIdentifierType::List(..) => {
-                                                    error(*ident_line, "Cannot change entire list");
-                                                }
-                                            };
-                                        match call.keyword {
-                                            TokenType::Set => {
-                                                self.scope.set_var(
-                                                    &ident.0,
-                                                    &mut vec![literal.clone()],
-                                                    true,
-                                                    start_line,
-                                                );
-                                                literal
-                                            }
-                                            TokenType::AddWith => match var {
-                                                LiteralOrFile::Literal(LiteralNode::Number(
-                                                    num,
-                                                )) => {
-                                                    if let LiteralOrFile::Literal(
-                                                        LiteralNode::Number(num2),
-                                                    ) = literal
-                                                    {
-                                                        let new_val = num + num2;
-                                                        self.scope.set_var(
-                                                            &ident.0,
-                                                            &mut vec![LiteralOrFile::Literal(
-                                                                LiteralNode::Number(new_val),
-                                                            )],
-                                                            true,
-                                                            start_line,
-                                                        );
-                                                        LiteralOrFile::Literal(LiteralNode::Number(
-                                                            new_val,
-                                                        ))
-                                                    } else {
-                                                        error(
-                                                            start_line,
-                                                            format!(
-                                                                "Variable {} is not a number",
-                                                                ident.0
-                                                            ),
-                                                        );
-                                                    }
-                                                }
-                                                LiteralOrFile::Literal(LiteralNode::String(
-                                                    mut s,
-                                                )) => match literal {
-                                                    LiteralOrFile::Literal(
-                                                        LiteralNode::String(s2),
-                                                    ) => {
-                                                        s.push_str(s2.as_str());
-                                                        self.scope.set_var(
-                                                            &ident.0,
-                                                            &mut vec![LiteralOrFile::Literal(
-                                                                LiteralNode::String(s.clone()),
-                                                            )],
-                                                            true,
-                                                            start_line,
-                                                        );
-                                                        LiteralOrFile::Literal(LiteralNode::String(
-                                                            s,
-                                                        ))
-                                                    }
-                                                    LiteralOrFile::Literal(
-                                                        LiteralNode::Number(n),
-                                                    ) => {
-                                                        s.push_str(&n.to_string());
-                                                        self.scope.set_var(
-                                                            &ident.0,
-                                                            &mut vec![LiteralOrFile::Literal(
-                                                                LiteralNode::String(s.clone()),
-                                                            )],
-                                                            true,
-                                                            start_line,
-                                                        );
-                                                        LiteralOrFile::Literal(LiteralNode::String(
-                                                            s,
-                                                        ))
-                                                    }
-                                                    LiteralOrFile::Literal(
-                                                        LiteralNode::Boolean(boolean),
-                                                    ) => {
-                                                        s.push_str(&boolean.to_string());
-                                                        self.scope.set_var(
-                                                            &ident.0,
-                                                            &mut vec![LiteralOrFile::Literal(
-                                                                LiteralNode::String(s.clone()),
-                                                            )],
-                                                            true,
-                                                            start_line,
-                                                        );
-                                                        LiteralOrFile::Literal(LiteralNode::String(
-                                                            s,
-                                                        ))
-                                                    }
-                                                    LiteralOrFile::Literal(LiteralNode::Hempty) => {
-                                                        s.push_str("hempty");
-                                                        self.scope.set_var(
-                                                            &ident.0,
-                                                            &mut vec![LiteralOrFile::Literal(
-                                                                LiteralNode::String(s.clone()),
-                                                            )],
-                                                            true,
-                                                            start_line,
-                                                        );
-                                                        LiteralOrFile::Literal(LiteralNode::String(
-                                                            s,
-                                                        ))
-                                                    }
-                                                    _ => {
-                                                        error(
-                                                            start_line,
-                                                            format!(
-                                                                "Variable {} is not a string",
-                                                                ident.0
-                                                            ),
-                                                        );
-                                                    }
-                                                },
-                                                _ => {
-                                                    error(
-                                                        start_line,
-                                                        format!(
-                                                            "Variable {} is not a number/string",
-                                                            ident.0
-                                                        ),
-                                                    );
-                                                }
-                                            },
-                                            TokenType::MultiplyWith => match var {
-                                                LiteralOrFile::Literal(LiteralNode::Number(
-                                                    num,
-                                                )) => {
-                                                    if let LiteralOrFile::Literal(
-                                                        LiteralNode::Number(num2),
-                                                    ) = literal
-                                                    {
-                                                        let new_val: f64 = num * num2;
-                                                        self.scope.set_var(
-                                                            &ident.0,
-                                                            &mut vec![LiteralOrFile::Literal(
-                                                                LiteralNode::Number(new_val),
-                                                            )],
-                                                            true,
-                                                            start_line,
-                                                        );
-                                                        LiteralOrFile::Literal(LiteralNode::Number(
-                                                            new_val,
-                                                        ))
-                                                    } else {
-                                                        error(
-                                                            start_line,
-                                                            format!(
-                                                                "Variable {} is not a number",
-                                                                ident.0
-                                                            ),
-                                                        );
-                                                    }
-                                                }
-                                                LiteralOrFile::Literal(LiteralNode::String(
-                                                    ref s,
-                                                )) => {
-                                                    if let LiteralOrFile::Literal(
-                                                        LiteralNode::Number(num),
-                                                    ) = literal
-                                                    {
-                                                        let new_string: String = (0..num as i32)
-                                                            .map(|_| s.to_string())
-                                                            .collect();
-                                                        self.scope.set_var(
-                                                            &ident.0,
-                                                            &mut vec![LiteralOrFile::Literal(
-                                                                LiteralNode::String(
-                                                                    new_string.clone(),
-                                                                ),
-                                                            )],
-                                                            true,
-                                                            start_line,
-                                                        );
-                                                        LiteralOrFile::Literal(LiteralNode::String(
-                                                            new_string,
-                                                        ))
-                                                    } else {
-                                                        error(
-                                                            start_line,
-                                                            format!(
-                                                                "Variable {} is not a number",
-                                                                ident.0
-                                                            ),
-                                                        );
-                                                    }
-                                                }
-                                                _ => {
-                                                    error(
-                                                        start_line,
-                                                        format!(
-                                                            "Variable {} is not a number/string",
-                                                            ident.0
-                                                        ),
-                                                    );
-                                                }
-                                            },
-                                            TokenType::SubtractWith | TokenType::DivideWith => {
-                                                if let LiteralOrFile::Literal(
-                                                    LiteralNode::Number(nums),
-                                                ) = var
-                                                {
-                                                    if let LiteralOrFile::Literal(
-                                                        LiteralNode::Number(num),
-                                                    ) = literal
-                                                    {
-                                                        if call.keyword == TokenType::SubtractWith {
-                                                            let new_val = nums - num;
-                                                            self.scope.set_var(
-                                                                &ident.0,
-                                                                &mut vec![LiteralOrFile::Literal(
-                                                                    LiteralNode::Number(new_val),
-                                                                )],
-                                                                true,
-                                                                start_line,
-                                                            );
-                                                            LiteralOrFile::Literal(
-                                                                LiteralNode::Number(new_val),
-                                                            )
-                                                        } else {
-                                                            let new_val = nums / num;
-                                                            self.scope.set_var(
-                                                                &ident.0,
-                                                                &mut vec![LiteralOrFile::Literal(
-                                                                    LiteralNode::Number(new_val),
-                                                                )],
-                                                                true,
-                                                                start_line,
-                                                            );
-                                                            LiteralOrFile::Literal(
-                                                                LiteralNode::Number(new_val),
-                                                            )
-                                                        }
-                                                    } else {
-                                                        error(
-                                                            start_line,
-                                                            format!(
-                                                                "Variable {} is not a number",
-                                                                ident.0
-                                                            ),
-                                                        );
-                                                    }
-                                                } else {
-                                                    error(
-                                                        start_line,
-                                                        format!(
-                                                            "Variable {} is not a number/string",
-                                                            ident.0
-                                                        ),
-                                                    );
-                                                }
-                                            }
-                                            _ => {
-                                                error(
-                                                    start_line,
-                                                    format!(
-                                                        "Invalid operator for literal {}",
-                                                        call.keyword
-                                                    ),
-                                                );
-                                            }
-                                        }
+                            if module_path(&ident.0).is_some() {
+                                return Err(error(
+                                    ident_span,
+                                    format!(
+                                        "cannot change {}, variables from modules are read-only",
+                                        ident.0
+                                    ),
+                                ));
+                            }
+                            if self.scope.borrow().has_var(&ident.0, true) {
+                                let mut new_stuff: Vec<Value> = call
+                                    .arguments
+                                    .iter()
+                                    .skip(1)
+                                    .map(|thing| self.find_pointer_in_stuff(thing))
+                                    .collect::<Result<_, _>>()?;
+                                let new_value = match new_stuff.len() {
+                                    1 if call.keyword == TokenType::Set => new_stuff.remove(0),
+                                    1 => update_value(
+                                        &call.keyword,
+                                        self.scope.borrow().get_var(&ident.0, span)?,
+                                        new_stuff.remove(0),
+                                        span,
+                                    )?,
+                                    2 if call.keyword == TokenType::Set => {
+                                        let cdr = new_stuff.remove(1);
+                                        Value::cons(new_stuff.remove(0), cdr)
                                     }
-                                    2 => {
-                                        if call.keyword == TokenType::Set {
-                                            self.scope.set_var(
-                                                &ident.0,
-                                                &mut new_stuff,
-                                                true,
-                                                start_line,
-                                            );
-                                            LiteralOrFile::Literal(LiteralNode::String(
-                                                new_stuff
-                                                    .iter()
-                                                    .map(std::string::ToString::to_string)
-                                                    .collect(),
-                                            ))
-                                        } else {
-                                            error(
-                                                start_line,
-                                                format!(
-                                                    "Too many arguments for function {}",
-                                                    call.keyword
-                                                )
-                                                .as_str(),
-                                            );
-                                        }
+                                    _ => {
+                                        return Err(error(
+                                            span,
+                                            format!(
+                                                "Too many arguments for function {}",
+                                                call.keyword
+                                            ),
+                                        ));
                                     }
-                                    _ => error(
-                                        start_line,
-                                        format!("Too many arguments for function {}", call.keyword)
-                                            .as_str(),
-                                    ),
-                                }
+                                };
+                                self.scope.borrow_mut().set_var(
+                                    &ident.0,
+                                    new_value.clone(),
+                                    true,
+                                    span,
+                                )?;
+                                new_value
                             } else {
-                                error(*ident_line, format!("Variable {} is not defined", ident.0));
+                                return Err(error(
+                                    ident_span,
+                                    format!("Variable {} is not defined", ident.0),
+                                ));
                             }
                         } else {
-                            error(
-                                start_line,
+                            return Err(error(
+                                span,
                                 format!("First argument of {} must be an identifier", call.keyword)
                                     .as_str(),
-                            );
+                            ));
                         }
                     }
                     TokenType::Open => {
-                        arg_error(
-                            1,
-                            call.arguments.len() as u32,
-                            &call.keyword,
-                            false,
-                            start_line,
-                        );
+                        arg_error(1, call.arguments.len() as u32, &call.keyword, false, span)?;
                         // check if the first argument is a string
                         let arg = self
-                            .find_pointer_in_stuff(&call.arguments[0])
-                            .get_string(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_string(span, &call.keyword)?;
                         if std::path::Path::new(&arg).exists() {
-                            LiteralOrFile::File(arg)
+                            Value::File(arg)
                         } else {
-                            error(
-                                start_line,
+                            return Err(error(
+                                span,
                                 format!("Could not open file {arg}: does not exist").as_str(),
-                            );
+                            ));
                         }
                     }
                     TokenType::Close | TokenType::Read => {
-                        arg_error(
-                            1,
-                            call.arguments.len() as u32,
-                            &call.keyword,
-                            false,
-                            start_line,
-                        );
+                        arg_error(1, call.arguments.len() as u32, &call.keyword, false, span)?;
                         // evalute args[0] and check if it is a file
-                        match &call.arguments[0] {
-                            Ast::Identifier(Identifier { node: ident, .. }) => {
-                                let files = self.scope.get_var(&ident.0, start_line);
-                                match files {
-                                    NewIdentifierType::Vairable(var) => {
-                                        match var.value {
-                                            LiteralOrFile::File(file) => {
-                                                // set idnetifier to nothing
-                                                match call.keyword {
-                                                    TokenType::Close => {
-                                                        self.scope.set_var(
-                                                            &ident.0,
-                                                            &mut vec![LiteralOrFile::Literal(
-                                                                LiteralNode::Hempty,
-                                                            )],
-                                                            true,
-                                                            start_line,
-                                                        );
-                                                    }
-                                                    TokenType::Read => {
-                                                        let contents = // create a string to hold the contents of the file
-                                                                match read_file(&file) {
-                                                                    Ok(contents) => contents,
-                                                                    Err(err) => {
-                                                                        error(start_line, format!("{err}"));
-                                                                    }
-                                                                }; // read the file into the string
-                                                        return LiteralOrFile::Literal(
-                                                            LiteralNode::String(contents),
-                                                        ); // return the string
-                                                    }
-                                                    _ => {}
-                                                }
-                                            }
-                                            _ => error(
-                                                start_line,
-                                                format!("{} is not a file", ident.0).as_str(),
-                                            ),
-                                        }
-                                    }
-                                    NewIdentifierType::List(_) => error(
-                                        start_line,
-                                        format!("Variable {} is not a file", ident.0).as_str(),
-                                    ),
-                                }
-                            }
-                            other => {
-                                match self.find_pointer_in_stuff(other) {
-                                    LiteralOrFile::File(file) => {
-                                        match call.keyword {
-                                            TokenType::Close => {
-                                                self.scope.files.remove(&file);
-                                            }
-                                            TokenType::Read => {
-                                                let contents: String = String::new(); // create a string to hold the contents of the file
-                                                match read_file(&file) {
-                                                    Ok(contents) => contents,
-                                                    Err(err) => {
-                                                        error(start_line, format!("{err}"));
-                                                    }
-                                                }; // read the file into the string
-                                                return LiteralOrFile::Literal(
-                                                    LiteralNode::String(contents),
-                                                ); // return the string
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                    _ => {
-                                        error(
-                                            start_line,
-                                            format!(
-                                                "First argument of {} must be a file",
-                                                call.keyword
-                                            )
-                                            .as_str(),
-                                        );
-                                    }
-                                };
-                            }
+                        let file = self
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_file(span, &call.keyword)?;
+                        if call.keyword == TokenType::Read {
+                            let contents =
+                                read_file(&file).map_err(|err| error(span, format!("{err}")))?;
+                            return Ok(Value::String(contents));
+                        }
+                        self.scope.borrow_mut().files.remove(&file);
+                        // set idnetifier to nothing
+                        if let Ast::Identifier(Identifier { node: ident, .. }) = &call.arguments[0]
+                        {
+                            self.scope
+                                .borrow_mut()
+                                .set_var(&ident.0, Value::Hempty, true, span)?;
                         }
-                        LiteralOrFile::Literal(LiteralNode::Hempty)
+                        Value::Hempty
                     }
                     TokenType::Write => {
                         //takes 3 arguments:
                         // 1. the file
                         // 2. the string to write
                         // 3. the mode (a: append or w: overwrite)
-                        arg_error(
-                            3,
-                            call.arguments.len() as u32,
-                            &call.keyword,
-                            false,
-                            start_line,
-                        );
+                        arg_error(3, call.arguments.len() as u32, &call.keyword, false, span)?;
                         // get the file
                         let file = self
-                            .find_pointer_in_stuff(&call.arguments[0])
-                            .get_file(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_file(span, &call.keyword)?;
                         // get the string
                         let string = self
-                            .find_pointer_in_stuff(&call.arguments[1])
-                            .get_string(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[1])?
+                            .get_string(span, &call.keyword)?;
                         // get the mode
                         let mode = self
-                            .find_pointer_in_stuff(&call.arguments[2])
-                            .get_string(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[2])?
+                            .get_string(span, &call.keyword)?;
                         // write the string to the file
                         match write_file(&file, &string, &mode) {
-                            Ok(_) => LiteralOrFile::Literal(LiteralNode::Hempty),
+                            Ok(()) => Value::Hempty,
                             Err(err) => {
-                                error(start_line, format!("{err}").as_str());
+                                return Err(error(span, format!("{err}").as_str()));
                             }
                         }
                     }
                     TokenType::ReadLine => {
                         // takes 2 arguments: file, line
-                        arg_error(
-                            2,
-                            call.arguments.len() as u32,
-                            &call.keyword,
-                            false,
-                            start_line,
-                        );
+                        arg_error(2, call.arguments.len() as u32, &call.keyword, false, span)?;
                         // get the file
                         let file = self
-                            .find_pointer_in_stuff(&call.arguments[0])
-                            .get_file(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_file(span, &call.keyword)?;
                         // get the line
                         let line = self
-                            .find_pointer_in_stuff(&call.arguments[1])
-                            .get_number(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[1])?
+                            .get_number(span, &call.keyword)?;
                         // read the the file
                         match read_file(&file) {
                             Ok(contents) => {
                                 let lines = contents.split('\n').collect::<Vec<&str>>();
-                                if line as usize > lines.len() {
-                                    error(
-                                        start_line,
+                                // lines start at 1, anything below becomes 0
+                                if line as usize == 0 || line as usize > lines.len() {
+                                    return Err(error(
+                                        span,
                                         format!("Line {line} does not exist in file {file}"),
-                                    );
+                                    ));
                                 }
-                                LiteralOrFile::Literal(LiteralNode::String(
-                                    lines[line as usize - 1].to_string(),
-                                ))
+                                Value::String(lines[line as usize - 1].to_string())
                             }
                             Err(err) => {
-                                error(start_line, format!("{err}").as_str());
+                                return Err(error(span, format!("{err}").as_str()));
                             }
                         }
                     }
                     TokenType::WriteLine => {
                         // takes 4 arguments: file, line, string and mode (a: append to beging of line or w: overwrite)
-                        arg_error(
-                            4,
-                            call.arguments.len() as u32,
-                            &call.keyword,
-                            false,
-                            start_line,
-                        );
+                        arg_error(4, call.arguments.len() as u32, &call.keyword, false, span)?;
                         // get the file
                         let file = self
-                            .find_pointer_in_stuff(&call.arguments[0])
-                            .get_file(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_file(span, &call.keyword)?;
                         // get the string
                         let mut string = self
-                            .find_pointer_in_stuff(&call.arguments[1])
-                            .get_string(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[1])?
+                            .get_string(span, &call.keyword)?;
                         // get the line
                         let line = self
-                            .find_pointer_in_stuff(&call.arguments[2])
-                            .get_number(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[2])?
+                            .get_number(span, &call.keyword)?;
                         // get the mode
                         let mode = self
-                            .find_pointer_in_stuff(&call.arguments[3])
-                            .get_string(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[3])?
+                            .get_string(span, &call.keyword)?;
                         // read the file
                         let mut contents = match read_file(&file) {
                             Ok(contents) => contents,
                             Err(err) => {
-                                error(start_line, format!("{err}").as_str());
+                                return Err(error(span, format!("{err}").as_str()));
                             }
                         };
                         // split the contents into lines
                         let mut lines = contents.split('\n').collect::<Vec<&str>>();
                         // if the line is greater than the number of lines, add a new line
-                        if line as usize > lines.len() {
-                            error(start_line, "Line does not exist in file");
+                        // lines start at 1, anything below becomes 0
+                        if line as usize == 0 || line as usize > lines.len() {
+                            return Err(error(span, "Line does not exist in file"));
                         }
                         string = match mode.as_str() {
                             "a" => {
-                                format!("{string}{}", lines[line as usize - 1],)
+                                format!("{string}{}", lines[line as usize - 1])
                             }
                             "w" => string,
                             _ => {
-                                error(
-                                    start_line,
+                                return Err(error(
+                                    span,
                                     format!("Mode {mode} is not a valid mode").as_str(),
-                                );
+                                ));
                             }
                         };
                         lines[line as usize - 1] = string.as_str();
@@ -1498,82 +1528,100 @@ impl Eval {
                         contents = lines.join("\n");
                         // write the file
                         match write_file(&file, &contents, "w") {
-                            Ok(_) => LiteralOrFile::Literal(LiteralNode::Hempty),
+                            Ok(()) => Value::Hempty,
                             Err(err) => {
-                                error(start_line, format!("{err}").as_str());
+                                return Err(error(span, format!("{err}").as_str()));
                             }
                         }
                     }
                     TokenType::DeleteFile | TokenType::CreateFile => {
                         // takes 1 argument: file
-                        arg_error(
-                            1,
-                            call.arguments.len() as u32,
-                            &call.keyword,
-                            false,
-                            start_line,
-                        );
+                        arg_error(1, call.arguments.len() as u32, &call.keyword, false, span)?;
                         // get the file
                         let file = self
-                            .find_pointer_in_stuff(&call.arguments[0])
-                            .get_file(start_line, &call.keyword);
+                            .find_pointer_in_stuff(&call.arguments[0])?
+                            .get_file(span, &call.keyword)?;
                         // match delete or create file
                         match call.keyword {
                             TokenType::DeleteFile => match fs::remove_file(&file) {
-                                Ok(_) => LiteralOrFile::Literal(LiteralNode::Hempty),
+                                Ok(()) => Value::Hempty,
                                 Err(err) => {
-                                    error(start_line, format!("{err}").as_str());
+                                    return Err(error(span, format!("{err}").as_str()));
                                 }
                             },
                             TokenType::CreateFile => {
                                 // create the file
-                                match OpenOptions::new().create(true).open(&file) {
-                                    Ok(_) => LiteralOrFile::File(file),
+                                match OpenOptions::new()
+                                    .create(true)
+                                    .truncate(false)
+                                    .write(true)
+                                    .open(&file)
+                                {
+                                    Ok(_) => Value::File(file),
                                     Err(err) => {
-                                        error(start_line, format!("{err}").as_str());
+                                        return Err(error(span, format!("{err}").as_str()));
                                     }
                                 }
                             }
-                            _ => LiteralOrFile::Literal(LiteralNode::Hempty),
+                            _ => Value::Hempty,
                         }
                     }
                     t => {
-                        let mut new_stuff: Vec<LiteralNode> = Vec::new();
-                        call.arguments.iter().for_each(|thing| {
-                            match self.find_pointer_in_stuff(thing) {
-                                LiteralOrFile::Literal(literal) => {
-                                    new_stuff.push(literal);
-                                }
-                                LiteralOrFile::File(_) => {
-                                    error(
-                                        start_line,
-                                        format!(
-                                            "Cannot use file as argument for function {}",
-                                            call.keyword
-                                        )
-                                        .as_str(),
-                                    );
-                                }
-                            }
-                        });
-                        LiteralOrFile::Literal(t.r#do(&new_stuff, start_line))
+                        let new_stuff: Vec<Value> = call
+                            .arguments
+                            .iter()
+                            .map(|thing| self.find_pointer_in_stuff(thing))
+                            .collect::<Result<_, _>>()?;
+                        t.r#do(&new_stuff, span)?
                     }
                 }
             }
-            Ast::Literal(lit) => LiteralOrFile::Literal(lit.node.clone()),
+            Ast::Literal(lit) => Value::from(lit.node.clone()),
+            Ast::FunctionIdentifier(function) => {
+                let function =
+                    self.get_function(function.node.name, &function.node.path, &function.span)?;
+                self.function_value(&function)
+            }
+            Ast::Lambda(lambda) => self.function_value(&Rc::new(Potato::new(
+                None,
+                lambda.node.body.node.0.clone(),
+                lambda.node.num_arguments,
+                lambda.node.extra_arguments,
+                &self.scope,
+            ))),
+            Ast::List(list) => {
+                let items = list
+                    .node
+                    .items
+                    .iter()
+                    .map(|item| self.find_pointer_in_stuff(item))
+                    .collect::<Result<_, _>>()?;
+                let tail = match &list.node.tail {
+                    Some(tail) => self.find_pointer_in_stuff(tail)?,
+                    None => Value::Hempty,
+                };
+                Value::dotted_list(items, tail)
+            }
             Ast::Block(block) => {
                 let block = block.clone();
-                self.eval_scope(block.node.0);
-                LiteralOrFile::Literal(LiteralNode::Hempty)
+                self.eval_scope(block.node.0)?;
+                Value::Hempty
             }
-            Ast::Function(_) => todo!(),
-            Ast::If(_) => todo!(),
-            Ast::Loop(_) => todo!(),
-            Ast::Break(_) => todo!(),
-            Ast::Continue(_) => todo!(),
-            Ast::Return(_) => todo!(),
-            Ast::Declaration(_) => todo!(),
-        }
+            Ast::Function(_) | Ast::Break(_) | Ast::Continue(_) | Ast::Return(_) => {
+                return Err(error(stuff.span(), "statement cannot be used as a value"));
+            }
+            Ast::If(_) | Ast::Match(_) | Ast::Try(_) | Ast::Loop(_) | Ast::Declaration(_) => {
+                match self.eval_statement(stuff.clone())? {
+                    Flow::Done(value) => value,
+                    Flow::Stop(_) => {
+                        return Err(error(
+                            stuff.span(),
+                            "break, continue and return cannot leave an if, match, try, loop or create used as a value",
+                        ))
+                    }
+                }
+            }
+        })
     }
 }
 
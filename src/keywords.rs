@@ -32,13 +32,20 @@ impl Keyword {
         keywords.insert("list".to_string(), TokenType::List);
         keywords.insert("car".to_string(), TokenType::Car);
         keywords.insert("cdr".to_string(), TokenType::Cdr);
+        keywords.insert("cadr".to_string(), TokenType::Cadr);
+        keywords.insert("cddr".to_string(), TokenType::Cddr);
+        keywords.insert("caddr".to_string(), TokenType::Caddr);
         keywords.insert("return".to_string(), TokenType::Return { value: None });
         keywords.insert("break".to_string(), TokenType::Break);
         keywords.insert("continue".to_string(), TokenType::Continue);
         keywords.insert("loop".to_string(), TokenType::Loop);
+        keywords.insert("over".to_string(), TokenType::Over);
         keywords.insert("potato".to_string(), TokenType::Potato);
         keywords.insert("if".to_string(), TokenType::If);
         keywords.insert("else".to_string(), TokenType::Else);
+        keywords.insert("match".to_string(), TokenType::Match);
+        keywords.insert("try".to_string(), TokenType::Try);
+        keywords.insert("rescue".to_string(), TokenType::Rescue);
         keywords.insert("input".to_string(), TokenType::Input);
         keywords.insert("new".to_string(), TokenType::New);
         keywords.insert("input".to_string(), TokenType::Input);
@@ -61,7 +68,22 @@ impl Keyword {
         keywords.insert("createfile".to_string(), TokenType::CreateFile);
         keywords.insert("deletefile".to_string(), TokenType::DeleteFile);
         keywords.insert("type".to_string(), TokenType::Type);
+        keywords.insert("length".to_string(), TokenType::Length);
+        keywords.insert("nth".to_string(), TokenType::Nth);
+        keywords.insert("append".to_string(), TokenType::Append);
+        keywords.insert("reverse".to_string(), TokenType::Reverse);
+        keywords.insert("last".to_string(), TokenType::Last);
+        keywords.insert("contains".to_string(), TokenType::Contains);
+        keywords.insert("join".to_string(), TokenType::Join);
+        keywords.insert("split".to_string(), TokenType::Split);
+        keywords.insert("map".to_string(), TokenType::Map);
+        keywords.insert("filter".to_string(), TokenType::Filter);
+        keywords.insert("reduce".to_string(), TokenType::Reduce);
+        keywords.insert("fold".to_string(), TokenType::Fold);
+        keywords.insert("sort-by".to_string(), TokenType::SortBy);
+        keywords.insert("range".to_string(), TokenType::Range);
         keywords.insert("module".to_string(), TokenType::Module);
+        keywords.insert("private".to_string(), TokenType::Private);
         if num != 0 {
             for (key, value) in &keywords.clone() {
                 keywords.remove(key);
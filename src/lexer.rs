@@ -1,6 +1,6 @@
 use crate::{
-    error::{self, error},
-    token::{Token, TokenType},
+    error::{error, Diagnostic, UmplError},
+    token::{Span, Token, TokenType},
 };
 use hexponent::FloatLiteral;
 
@@ -8,9 +8,13 @@ use unic_emoji_char as emoji;
 pub struct Lexer {
     token_list: Vec<Token>,
     source: String,
-    start: usize,
-    current: usize,
+    start: usize,   // byte offset of the start of the current lexeme
+    current: usize, // byte offset of the next character
     line: i32,
+    column: i32,
+    token_start: usize, // byte offset of the start of the current token (for its span)
+    start_line: i32,
+    start_column: i32,
     name: String,
 }
 
@@ -19,28 +23,40 @@ impl Lexer {
         Self {
             token_list: Vec::new(),
             source,
-            start: 0,   // bytes
-            current: 0, // actual number of bytes in source
+            start: 0,
+            current: 0,
             line: 1,
+            column: 1,
+            token_start: 0,
+            start_line: 1,
+            start_column: 1,
             name,
         }
     }
 
-    pub fn scan_tokens(mut self) -> Vec<Token> {
+    pub fn scan_tokens(mut self) -> Result<Vec<Token>, UmplError> {
         while !self.is_at_end() {
-            self.start = self.current;
-            self.scan_token();
+            self.start_token();
+            self.scan_token()?;
         }
+        self.start_token();
         self.token_list
-            .push(Token::new(TokenType::EOF, "", self.line, &self.name));
-        self.token_list
+            .push(Token::new(TokenType::EOF, "", self.span()));
+        Ok(self.token_list)
     }
 
     fn is_at_end(&self) -> bool {
-        (self.current) >= (self.source.chars().count())
+        self.current >= self.source.len()
+    }
+
+    fn start_token(&mut self) {
+        self.start = self.current;
+        self.token_start = self.current;
+        self.start_line = self.line;
+        self.start_column = self.column;
     }
 
-    fn scan_token(&mut self) {
+    fn scan_token(&mut self) -> Result<(), UmplError> {
         let c: char = self.advance();
         match c {
             '(' => self.add_token(TokenType::LeftParen),
@@ -60,22 +76,21 @@ impl Lexer {
             '.' => self.add_token(TokenType::Dot),
             '<' => self.add_token(TokenType::LessThanSymbol),
             '>' => self.add_token(TokenType::GreaterThanSymbol),
-            '\n' => self.line += 1,
-            '`' => self.string(),
-            '$' => self.function_agument(),
+            '`' => self.string()?,
+            '$' => self.function_agument()?,
             '*' => self.add_token(TokenType::Star),
             c => {
                 if c.is_lowercase() || c == '-' {
                     if c == 't' || c == 'f' {
                         if !self.boolean() {
-                            self.identifier();
+                            self.identifier()?;
                         }
                     } else if c == 'h' {
                         if !self.hempty() {
-                            self.identifier();
+                            self.identifier()?;
                         }
                     } else {
-                        self.identifier();
+                        self.identifier()?;
                     }
                 } else if c.is_ascii_whitespace() {
                 } else if c.is_ascii_digit() {
@@ -83,17 +98,18 @@ impl Lexer {
                         self.advance();
                         self.start += 2;
                     }
-                    self.number();
+                    self.number()?;
                 } else if emoji::is_emoji(c) {
                     self.add_unicode_token(TokenType::FunctionIdentifier {
                         name: c,
                         path: vec![],
                     });
                 } else {
-                    error::error(self.line, format!("uknown character {c}"));
+                    return Err(error(&self.span(), format!("uknown character {c}")));
                 }
             }
         }
+        Ok(())
     }
 
     fn boolean(&mut self) -> bool {
@@ -121,131 +137,96 @@ impl Lexer {
         false
     }
 
-    #[allow(clippy::too_many_lines)]
-    fn string(&mut self) {
+    fn string(&mut self) -> Result<(), UmplError> {
+        // escape sequences are decoded into their own buffer so the source is never modified
+        let mut literal = String::new();
         while self.peek() != '`' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1;
-            }
-            // check for escape sequence \` \n \\ \t \r \a \b \f \v \e \Xhh \0ooo \Uhhhhhhhh
             if self.peek() == '\\' {
-                self.remove_text(self.current);
-                if self.peek() == '`' {
-                    self.advance();
-                } else if self.peek() == 'n' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\n');
-                    self.advance();
-                } else if self.peek() == '\\' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\\');
-                    self.advance();
-                } else if self.peek() == 't' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\t');
-                    self.advance();
-                } else if self.peek() == 'r' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\r');
-                    self.advance();
-                } else if self.peek() == 'a' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\x07');
-                    self.advance();
-                } else if self.peek() == 'b' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\x08');
-                    self.advance();
-                } else if self.peek() == 'f' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\x0C');
-                    self.advance();
-                } else if self.peek() == 'v' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\x0b');
-                    self.advance();
-                } else if self.peek() == 'e' {
-                    self.remove_text(self.current);
-                    self.insert_text(self.current, '\x1b');
-                    self.advance();
-                } else if self.peek() == 'x' {
-                    self.remove_text(self.current);
-                    match self.remove_text(self.current) {
-                        x if x.is_ascii_hexdigit() => match self.remove_text(self.current) {
-                            y if y.is_ascii_hexdigit() => {
-                                self.insert_text(
-                                    self.current,
-                                    u8::from_str_radix(format!("{x}{y}").as_str(), 16)
-                                        .unwrap_or_else(|_| {
-                                            error::error(self.line, "invalid hex escape sequence");
-                                        }) as char,
-                                );
-                                self.advance();
-                            }
-                            y => {
-                                self.insert_text(
-                                    self.current,
-                                    u8::from_str_radix(format!("{x}").as_str(), 16).unwrap_or_else(
-                                        |_| {
-                                            error::error(self.line, "invalid hex escape sequence");
-                                        },
-                                    ) as char,
-                                );
-                                self.insert_text(self.current + 1, y);
-                                self.advance();
-                            }
-                        },
-                        x => {
-                            self.insert_text(self.current, x);
-                            self.advance();
-                        }
-                    }
-                } else if self.peek() == 'u' {
-                    self.remove_text(self.current);
-                    // can be from 0 to 6 hex digits
-                    // loop until either 6 digits or we find a digit a non-hex digit
-                    // while looping remove the character and append to a string
-                    let mut hex_string = String::new();
-                    let mut i = 0;
-                    while i < 6 && self.peek().is_ascii_hexdigit() {
-                        hex_string.push(self.remove_text(self.current));
-                        i += 1;
-                    }
-                    self.insert_text(
-                        self.current,
-                        char::from_u32(
-                            u32::from_str_radix(hex_string.as_str(), 16).unwrap_or_else(|_| {
-                                error::error(self.line, "invalid unicode escape sequence");
-                            }),
-                        )
-                        .unwrap_or_else(|| {
-                            error::error(self.line, "invalid unicode escape sequence");
-                        }),
-                    );
-                    self.current += 1;
-                } else {
-                    error::error(
-                        self.line,
-                        format!("unknown escape sequence {}", self.peek()),
-                    );
-                }
-            } else {
                 self.advance();
+                self.escape_sequence(&mut literal)?;
+            } else {
+                literal.push(self.advance());
             }
         }
         if self.is_at_end() {
-            error::error(self.line, "unterminated string");
+            return Err(self.unterminated_string());
         }
         self.advance();
-        self.start += 1;
-        self.current -= 1;
-        let string = self.get_text();
-        self.start -= 1;
-        self.current += 1;
-        self.add_token(TokenType::String { literal: string });
+        let lexeme = format!("`{literal}`");
+        self.token_list.push(Token::new(
+            TokenType::String { literal },
+            &lexeme,
+            self.span(),
+        ));
+        Ok(())
     }
 
-    fn number(&mut self) {
+    // check for escape sequence \` \n \\ \t \r \a \b \f \v \e \xhh \uhhhhhh
+    fn escape_sequence(&mut self, literal: &mut String) -> Result<(), UmplError> {
+        if self.is_at_end() {
+            return Err(self.unterminated_string());
+        }
+        match self.advance() {
+            '`' => literal.push('`'),
+            'n' => literal.push('\n'),
+            '\\' => literal.push('\\'),
+            't' => literal.push('\t'),
+            'r' => literal.push('\r'),
+            'a' => literal.push('\x07'),
+            'b' => literal.push('\x08'),
+            'f' => literal.push('\x0C'),
+            'v' => literal.push('\x0b'),
+            'e' => literal.push('\x1b'),
+            'x' => {
+                if self.is_at_end() {
+                    return Err(error(&self.span(), "invalid hex escape sequence"));
+                }
+                let x = self.advance();
+                if x.is_ascii_hexdigit() {
+                    // one or two hex digits, if the second character is not a hex digit it stays part of the string
+                    let mut hex_string = String::from(x);
+                    if self.peek().is_ascii_hexdigit() {
+                        hex_string.push(self.advance());
+                    }
+                    let byte = u8::from_str_radix(&hex_string, 16)
+                        .map_err(|_| error(&self.span(), "invalid hex escape sequence"))?;
+                    literal.push(byte as char);
+                } else {
+                    literal.push(x);
+                }
+            }
+            'u' => {
+                // can be from 0 to 6 hex digits
+                let mut hex_string = String::new();
+                while hex_string.len() < 6 && self.peek().is_ascii_hexdigit() {
+                    hex_string.push(self.advance());
+                }
+                let unicode = u32::from_str_radix(&hex_string, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| error(&self.span(), "invalid unicode escape sequence"))?;
+                literal.push(unicode);
+            }
+            c => {
+                return Err(error(&self.span(), format!("unknown escape sequence {c}")));
+            }
+        }
+        Ok(())
+    }
+
+    fn unterminated_string(&self) -> UmplError {
+        let opening = Span::new(
+            &self.name,
+            (self.start_line, self.start_column, self.token_start),
+            (self.start_line, self.start_column + 1, self.token_start + 1),
+        );
+        Diagnostic::error(&opening, "unterminated string")
+            .with_primary_label(&opening, "string starts here")
+            .with_note("strings are closed with a `")
+            .into()
+    }
+
+    fn number(&mut self) -> Result<(), UmplError> {
         while self.peek().is_ascii_hexdigit() {
             self.advance();
         }
@@ -257,53 +238,72 @@ impl Lexer {
         }
         let number: FloatLiteral = format!("0x{}", self.get_text())
             .parse()
-            .expect("could not parse number");
+            .map_err(|_| error(&self.span(), "could not parse number"))?;
         self.add_token(TokenType::Number {
             literal: number.convert::<f64>().inner(),
         });
+        Ok(())
     }
 
-    fn identifier(&mut self) {
+    fn identifier(&mut self) -> Result<(), UmplError> {
         while self.peek().is_lowercase() || self.peek() == '-' || self.peek().is_numeric() {
             self.advance();
         }
         if self.peek() == '$' {
-            let first_path = self.get_text().pop();
-            // we can assume that the character is ascci and can use .len on string
-            if self.get_text().len() != 1 || !first_path.unwrap().is_alphabetic() {
-                error(self.line, format!("Module path has more than one character and or is non alphabetic, given module path: {}", self.get_text()));
-            };
-            let mut path = vec![first_path.unwrap()];
+            let first_path = self.get_text();
+            if !is_module_name(&first_path) {
+                return Err(error(
+                    &self.span(),
+                    format!("Module names are kebab-case, given module path: {first_path}"),
+                ));
+            }
+            let mut path = vec![first_path];
             self.advance();
-
-            // see if the next character is an emoji
-            while self.peek().is_lowercase() && self.peek_next() == '$' {
-                path.push(self.advance());
+            // every module in the path is followed by a $, at the end is either an emoji for a
+            // function or the name of a variable in the module
+            while !emoji::is_emoji(self.peek()) {
+                let module_start = self.current;
+                while self.peek().is_lowercase() || self.peek() == '-' || self.peek().is_numeric() {
+                    self.advance();
+                }
+                let module = self.source[module_start..self.current].to_string();
+                if module.is_empty() {
+                    return Err(error(
+                        &self.span(),
+                        format!("Unexpected character after $ {}", self.peek()),
+                    ));
+                }
+                if self.peek() != '$' {
+                    self.add_token(TokenType::Identifier {
+                        name: self.get_text(),
+                    });
+                    return Ok(());
+                }
+                if !is_module_name(&module) {
+                    return Err(error(
+                        &self.span(),
+                        format!("Module names are kebab-case, given module path: {module}"),
+                    ));
+                }
+                path.push(module);
                 self.advance();
             }
-            if emoji::is_emoji(self.peek()) {
-                let name = self.advance();
-                // maybe need to rework lexer so don't have to split after already done lexing
-                self.add_unicode_token(TokenType::FunctionIdentifier { name, path });
-            } else {
-                // error out
-                error::error(
-                    self.line,
-                    format!("Unexpected character after $ {}", self.peek()),
-                );
-            }
+            let name = self.advance();
+            // maybe need to rework lexer so don't have to split after already done lexing
+            self.add_unicode_token(TokenType::FunctionIdentifier { name, path });
         } else {
-            self.add_token(crate::KEYWORDS.get(&self.get_text()).unwrap_or(
+            self.add_token(crate::KEYWORDS.get(&self.get_text()).unwrap_or_else(|| {
                 TokenType::Identifier {
                     name: self.get_text(),
-                },
-            ));
+                }
+            }));
         }
+        Ok(())
     }
 
-    fn function_agument(&mut self) {
+    fn function_agument(&mut self) -> Result<(), UmplError> {
         self.start += 1; // advance start past the $ so that we can parse it into a number
-        let hex_char = vec!['A', 'B', 'C', 'D', 'E', 'F'];
+        let hex_char = ['A', 'B', 'C', 'D', 'E', 'F'];
         while self.peek().is_ascii_digit() || hex_char.contains(&self.peek()) {
             self.advance();
         }
@@ -313,73 +313,70 @@ impl Lexer {
                 Ok(contents) => {
                     contents.convert::<f64>().inner()
                 }
-                Err(error) => {
-                    error::error(self.line, error.to_string().as_str());
+                Err(err) => {
+                    return Err(error(&self.span(), err));
                 }
             }
         );
         self.add_token(TokenType::Identifier { name: identifier });
+        Ok(())
     }
 
     fn advance(&mut self) -> char {
-        self.current += 1;
-        let char_vec: Vec<char> = self.source.chars().collect();
-        char_vec[self.current - 1]
+        let c = self.peek();
+        self.current += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        c
     }
 
-    fn add_token(&mut self, token_type: TokenType) {
-        let text: std::str::Chars<'_> = self.source.chars();
-        let mut final_text: String = String::new();
-        text.enumerate().for_each(|i| {
-            if i.0 >= self.start && i.0 < self.current {
-                final_text.push(i.1);
-            }
-        });
-        self.token_list.push(Token::new(
-            token_type,
-            final_text.as_str(),
-            self.line,
+    fn span(&self) -> Span {
+        Span::new(
             &self.name,
-        ));
+            (self.start_line, self.start_column, self.token_start),
+            (self.line, self.column, self.current),
+        )
+    }
+
+    fn add_token(&mut self, token_type: TokenType) {
+        let text = self.get_text();
+        self.token_list
+            .push(Token::new(token_type, &text, self.span()));
     }
 
     fn add_unicode_token(&mut self, token_type: TokenType) {
-        let text: String = format!("{}", self.source.chars().nth(self.start).expect("Error"));
+        let text: String = self.source[self.start..]
+            .chars()
+            .next()
+            .expect("Error")
+            .to_string();
         self.token_list
-            .push(Token::new(token_type, &text, self.line, &self.name));
+            .push(Token::new(token_type, &text, self.span()));
     }
 
     fn peek(&self) -> char {
-        self.source.chars().nth(self.current).unwrap_or('\0')
+        self.source[self.current..].chars().next().unwrap_or('\0')
     }
 
     fn peek_next(&self) -> char {
-        self.source.chars().nth(self.current + 1).unwrap_or('\0')
+        self.source[self.current..].chars().nth(1).unwrap_or('\0')
     }
 
     fn get_text(&self) -> String {
-        let text: std::str::Chars<'_> = self.source.chars();
-        let mut final_text: String = String::new();
-        text.enumerate().for_each(|i| {
-            if i.0 >= self.start && i.0 < self.current {
-                final_text.push(i.1);
-            }
-        });
-        final_text
-    }
-
-    fn remove_text(&mut self, pos: usize) -> char {
-        // use chars to be able to use unicode, remove the pos
-        let mut text: Vec<char> = self.source.chars().collect();
-        let to_return = text[pos];
-        text.remove(pos);
-        self.source = text.iter().collect();
-        to_return
+        self.source[self.start..self.current].to_string()
     }
+}
 
-    fn insert_text(&mut self, pos: usize, texts: char) {
-        let mut text: Vec<char> = self.source.chars().collect();
-        text.insert(pos, texts);
-        self.source = text.iter().collect();
-    }
+/// module names are kebab-case: lowercase letters and digits with single dashes between them, starting with a letter
+pub fn is_module_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && !name.ends_with('-')
+        && !name.contains("--")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
 }
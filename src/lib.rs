@@ -7,6 +7,7 @@
     clippy::match_wildcard_for_single_variants,
     clippy::cast_possible_truncation,
     clippy::cast_sign_loss,
+    clippy::cast_precision_loss,
     clippy::cognitive_complexity,
     clippy::float_cmp,
     clippy::similar_names,
@@ -19,10 +20,85 @@ pub mod keywords;
 pub mod lexer;
 pub mod parser;
 pub mod token;
+pub mod value;
 
 use lazy_static::lazy_static;
+use std::{collections::HashMap, path::PathBuf};
 
-use crate::keywords::Keyword;
+use crate::{
+    error::{Diagnostic, UmplError},
+    eval::Eval,
+    keywords::Keyword,
+    lexer::Lexer,
+    parser::Parser,
+    value::Value,
+};
 lazy_static! {
     pub static ref KEYWORDS: Keyword = Keyword::new();
 }
+
+/// Lexes, parses and evaluates `src`, `name` is the filename used in diagnostics.
+/// Nothing here exits the process, errors and calls to `exit` are returned as an [`UmplError`].
+///
+/// Warnings are dropped, use [`run_source_with_warnings`] to get them.
+pub fn run_source(src: &str, name: &str) -> Result<Value, UmplError> {
+    run_source_with_search_path(src, name, &[])
+}
+
+/// Like [`run_source`], but modules are also looked for in `search_path`.
+pub fn run_source_with_search_path(
+    src: &str,
+    name: &str,
+    search_path: &[PathBuf],
+) -> Result<Value, UmplError> {
+    run_source_with_warnings(src, name, search_path).0
+}
+
+/// Like [`run_source_with_search_path`], but also gives back the warnings of `src` and the modules it
+/// opened, they are there even if running failed.
+pub fn run_source_with_warnings(
+    src: &str,
+    name: &str,
+    search_path: &[PathBuf],
+) -> (Result<Value, UmplError>, Vec<Diagnostic>) {
+    let mut warnings = Vec::new();
+    let result = run(src, name, search_path, &mut warnings);
+    (result, warnings)
+}
+
+fn run(
+    src: &str,
+    name: &str,
+    search_path: &[PathBuf],
+    warnings: &mut Vec<Diagnostic>,
+) -> Result<Value, UmplError> {
+    let mut sources = HashMap::from([(name.to_string(), src.to_string())]);
+    let mut found = Vec::new();
+    let result = eval_source(src, name, search_path, &mut found, &mut sources);
+    // the sources are only all known once running is done, a diagnostic can point into any module
+    warnings.extend(
+        found
+            .into_iter()
+            .map(|warning| warning.with_sources(&sources)),
+    );
+    result.map_err(|error| error.with_sources(&sources))
+}
+
+fn eval_source(
+    src: &str,
+    name: &str,
+    search_path: &[PathBuf],
+    warnings: &mut Vec<Diagnostic>,
+    sources: &mut HashMap<String, String>,
+) -> Result<Value, UmplError> {
+    let tokens = Lexer::new(src.to_string(), name.to_string()).scan_tokens()?;
+    let mut parser = Parser::new(tokens, name.to_string());
+    let ast = parser.parse();
+    warnings.extend(parser.take_warnings());
+    let ast = ast?;
+    let mut eval = Eval::new().with_file(name).with_search_path(search_path);
+    let result = eval.eval_program(ast);
+    warnings.extend(eval.take_warnings());
+    sources.extend(eval.take_sources());
+    result
+}
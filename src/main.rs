@@ -3,10 +3,13 @@ use std::{
     env,
     fs::File,
     io::{self, Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
     process::exit,
 };
-use umpl::{cli, error, eval::Eval, lexer::Lexer, parser::Parser};
+use umpl::{
+    cli,
+    error::{self, Diagnostic, Severity, UmplError},
+};
 
 fn main() {
     let args: Vec<String> = env::args().collect(); // get the args
@@ -47,7 +50,7 @@ fn main() {
                         io::stdin()
                             .read_line(&mut y_or_n)
                             .unwrap_or_else(|_| panic!("Failed to read stdin")); // read the input
-                        if y_or_n == "n" {
+                        if y_or_n.trim_end() == "n" {
                             // if the user does not want to overwrite the file exit
                             exit(0);
                         }
@@ -61,7 +64,7 @@ fn main() {
                 }
                 break 'l;
             } else if input.trim() == "run" {
-                run(current_repl.to_string(), "<stdin>"); // run the current repl
+                run(&current_repl, "<stdin>", &parsed_args.include, true); // run the current repl
                 input.clear();
             } else {
                 current_repl.push_str(&input); // add the input to the current line of the repl
@@ -71,23 +74,37 @@ fn main() {
     } else {
         // if we are not in repl mode ie we are reading a file
         let mut file: File = File::open(&parsed_args.file).unwrap_or_else(|_| {
-            error::error(0, "Error encountered while opening file!");
+            error::fatal(&Diagnostic::new(
+                Severity::Error,
+                "Error encountered while opening file!",
+            ));
         }); // open the file
         let mut contents: String = String::new(); // create a string to hold the contents of the file
         match file.read_to_string(&mut contents) {
             Ok(contents) => contents,
             Err(_) => {
-                error::error(0, "could not read file");
+                error::fatal(&Diagnostic::new(Severity::Error, "could not read file"));
             }
         }; // read the file into the string
-        run(contents, &parsed_args.file); // run the file
+        run(&contents, &parsed_args.file, &parsed_args.include, false); // run the file
     }
 }
 
-fn run(line: String, name: &str) {
-    let lexer: Lexer = Lexer::new(line, name.to_string());
-    let lexed = lexer.scan_tokens();
-    let mut parsed: Parser = Parser::new(lexed, name.to_string());
-    let parsed = parsed.parse();
-    Eval::new().eval_scope(parsed);
+fn run(line: &str, name: &str, include: &[String], repl: bool) {
+    let search_path: Vec<PathBuf> = include.iter().map(PathBuf::from).collect();
+    let (result, warnings) = umpl::run_source_with_warnings(line, name, &search_path);
+    for warning in warnings {
+        warning.report();
+    }
+    match result {
+        Ok(_) => {}
+        Err(UmplError::Exit(code)) => exit(code),
+        Err(error) => {
+            error.report();
+            // an error in the repl should not end the session
+            if !repl {
+                exit(1);
+            }
+        }
+    }
 }
@@ -1,12 +1,14 @@
 pub(crate) mod rules;
 use crate::{
-    error::error,
+    error::{error, Diagnostic, UmplError},
     parser::rules::{
         BlockNode, Break, BreakNode, CallNode, Continue, ContinueNode, Declaration,
-        DeclarationNode, Function, FunctionNode, If, IfNode, Loop, LoopNode, PrintType, Return,
-        ReturnNode,
+        DeclarationNode, Function, FunctionIdentifier, FunctionIdentifierNode, FunctionNode, If,
+        IfBranch, IfNode, Lambda, LambdaNode, List, ListNode, Loop, LoopNode, LoopOver, Match,
+        MatchArm, MatchNode, Pattern, PrintType, Return, ReturnNode, Try, TryNode,
     },
-    token::{Token, TokenType},
+    token::{Span, Token, TokenType},
+    value::Value,
 };
 use log::{debug, info, warn};
 
@@ -19,523 +21,928 @@ pub struct Parser {
     tokens: Vec<Token>,
     token: Token,
     done: bool,
-    in_function: bool,
-    in_loop: bool,
+    // the loops and functions the parser is inside of, the innermost is last
+    blocks: Vec<BlockType>,
     variables: Vec<String>,
-    filename: String,
+    // every syntax error found so far, reported together once parsing is done
+    errors: Vec<Diagnostic>,
+    // things that are allowed but probably a mistake, the caller decides whether to report them
+    warnings: Vec<Diagnostic>,
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>, name: String) -> Self {
+    pub fn new(mut tokens: Vec<Token>, name: String) -> Self {
+        let eof = Token::new(
+            TokenType::EOF,
+            "",
+            Span {
+                filename: name,
+                ..Span::default()
+            },
+        );
+        // advancing and looking ahead stop at the last token,
+        // so there always has to be an EOF to stop at
+        if !matches!(
+            tokens.last(),
+            Some(Token {
+                token_type: TokenType::EOF,
+                ..
+            })
+        ) {
+            tokens.push(eof.clone());
+        }
         Self {
             paren_count: 0,
             current_position: 0,
             tokens,
-            token: Token {
-                token_type: TokenType::EOF,
-                line: 0,
-                lexeme: String::new(),
-                filename: name.clone(),
-            },
+            token: eof,
             done: false,
             weird_bracket_count: 0,
-            in_function: false,
-            in_loop: false,
+            blocks: Vec::new(),
             variables: Vec::new(),
-            filename: name,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn in_function(&self) -> bool {
+        self.blocks.contains(&BlockType::Function)
+    }
+
+    /// a loop inside a function can break, but a function inside a loop cannot break out of the loop
+    fn in_loop(&self) -> bool {
+        matches!(self.blocks.last(), Some(BlockType::Loop(_)))
+    }
+
+    /// checks that a loop with the label is around the break or continue, without going past a function
+    fn has_loop_label(&self, label: &str) -> bool {
+        self.blocks
+            .iter()
+            .rev()
+            .take_while(|block| **block != BlockType::Function)
+            .any(|block| matches!(block, BlockType::Loop(Some(name)) if name == label))
+    }
+
+    /// parses `list name with [items]`
+    fn list_declaration(&mut self) -> Result<Ast, UmplError> {
+        let start_span = self.token.span.clone();
+        self.advance("list_declaration")?;
+        let TokenType::Identifier { name } = self.token.token_type.clone() else {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "identifier expected, after \"list\" found TokenType::{:?}",
+                    self.token.token_type
+                ),
+            ));
+        };
+        info!("list identifier found");
+        self.advance("list_declaration")?;
+        if self.token.token_type != TokenType::With {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "with keyword expected, found TokenType::{:?}",
+                    self.token.token_type
+                ),
+            ));
+        }
+        self.advance("list_declaration")?;
+        if self.token.token_type != TokenType::LeftBracket {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "left bracket expected after \"with\", found {}",
+                    self.token.token_type
+                ),
+            ));
+        }
+        info!("list with");
+        let list = self.list()?;
+        self.variables.push(name.clone());
+        Ok(Ast::Declaration(Declaration::new(
+            DeclarationNode::new(name, list),
+            self.span_from(&start_span),
+        )))
+    }
+
+    /// parses `create name with value`
+    fn create(&mut self) -> Result<Ast, UmplError> {
+        let start_span = self.token.span.clone();
+        self.advance("create")?;
+        let TokenType::Identifier { name } = self.token.token_type.clone() else {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "identifier expected after \"create\", found TokenType::{:?}",
+                    self.token.token_type
+                ),
+            ));
+        };
+        info!("create identifier found");
+        self.advance("create")?;
+        if self.token.token_type != TokenType::With {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "with keyword expected, found TokenType::{:?}",
+                    self.token.token_type
+                ),
+            ));
+        }
+        self.advance("create")?;
+        info!("create identifier with {}", self.token.token_type);
+        let thing = self.get_value()?;
+        self.variables.push(name.clone());
+        Ok(Ast::Declaration(Declaration::new(
+            DeclarationNode::new(name, thing),
+            self.span_from(&start_span),
+        )))
+    }
+
+    /// parses `loop label over name with items ⧼body⧽`, the label and over are optional
+    fn loop_statement(&mut self) -> Result<Ast, UmplError> {
+        info!("loop found");
+        let start_span = self.token.span.clone();
+        let label = match self.peek().token_type.clone() {
+            TokenType::Identifier { name } => {
+                self.advance("loop_statement found loop label")?;
+                Some(name)
+            }
+            _ => None,
+        };
+        let over = self.loop_over()?;
+        let loop_body = self.parse_block(BlockType::Loop(label.clone()))?;
+        info!("Done parsing loop body");
+        Ok(Ast::Loop(Loop::new(
+            LoopNode::new(loop_body, label, over),
+            self.span_from(&start_span),
+        )))
+    }
+
+    /// parses an if with its else ifs and else
+    fn if_statement(&mut self) -> Result<Ast, UmplError> {
+        let start_span = self.token.span.clone();
+        let mut branches = vec![self.if_branch()?];
+        let mut body_false = None;
+        // else if adds to the same if instead of nesting another one in the else
+        while self.peek().token_type == TokenType::Else {
+            self.advance("if_statement found else")?;
+            if self.peek().token_type == TokenType::If {
+                self.advance("if_statement found else if")?;
+                branches.push(self.if_branch()?);
+            } else {
+                body_false = Some(self.parse_block(BlockType::None)?);
+                break;
+            }
+        }
+        Ok(Ast::If(If::new(
+            IfNode::new(branches, body_false),
+            self.span_from(&start_span),
+        )))
+    }
+
+    /// parses `match value ⧼pattern ⧼body⧽ ... else ⧼body⧽⧽`, the else is optional but has to be last
+    fn match_statement(&mut self) -> Result<Ast, UmplError> {
+        info!("match found");
+        let start_span = self.token.span.clone();
+        self.advance("match_statement expecting value")?;
+        let value = self.get_value()?;
+        self.advance("match_statement expecting arms")?;
+        if self.token.token_type != TokenType::CodeBlockBegin {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "⧼ expected after the value of a match, found {}",
+                    self.token.token_type
+                ),
+            ));
+        }
+        let mut arms = Vec::new();
+        let mut default = None;
+        loop {
+            // an arm left open takes the rest of the file with it
+            if !self.done {
+                self.advance("match_statement expecting pattern")?;
+            }
+            match self.token.token_type {
+                TokenType::CodeBlockEnd => break,
+                TokenType::EOF => {
+                    return Err(Diagnostic::error(&self.token.span, "unclosed match")
+                        .with_primary_label(&self.token.span, "expected ⧽ before end of file")
+                        .with_label(&start_span, "match starts here")
+                        .into());
+                }
+                _ if default.is_some() => {
+                    return Err(error(
+                        &self.token.span,
+                        "else has to be the last arm of a match",
+                    ));
+                }
+                TokenType::Else => default = Some(self.parse_block(BlockType::None)?),
+                _ => {
+                    let pattern = self.pattern()?;
+                    arms.push(MatchArm::new(pattern, self.parse_block(BlockType::None)?));
+                }
+            }
+        }
+        let span = self.span_from(&start_span);
+        if default.is_none() && !is_exhaustive(&arms) {
+            self.warnings.push(
+                Diagnostic::warning(&span, "match is not exhaustive")
+                    .with_note("add an else arm, a value that fits no pattern does nothing"),
+            );
+        }
+        Ok(Ast::Match(Match::new(
+            MatchNode::new(value, arms, default),
+            span,
+        )))
+    }
+
+    /// parses `try ⧼body⧽ rescue name ⧼body⧽`
+    fn try_statement(&mut self) -> Result<Ast, UmplError> {
+        info!("try found");
+        let start_span = self.token.span.clone();
+        let body = self.parse_block(BlockType::None)?;
+        // a body left open takes the rest of the file with it, so there is no rescue to find
+        if !self.done {
+            self.advance("try_statement expecting rescue")?;
+        }
+        if self.token.token_type != TokenType::Rescue {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "rescue expected after the body of a try, found {}",
+                    self.token.token_type
+                ),
+            ));
+        }
+        self.advance("try_statement expecting identifier")?;
+        let TokenType::Identifier { name } = self.token.token_type.clone() else {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "identifier expected after \"rescue\", found {}",
+                    self.token.token_type
+                ),
+            ));
+        };
+        self.variables.push(name.clone());
+        let rescue = self.parse_block(BlockType::None)?;
+        Ok(Ast::Try(Try::new(
+            TryNode::new(body, name, rescue),
+            self.span_from(&start_span),
+        )))
+    }
+
+    /// parses a literal, `type name` or `[car . cdr]` pattern of a match arm
+    fn pattern(&mut self) -> Result<Pattern, UmplError> {
+        Ok(match self.token.token_type.clone() {
+            TokenType::Number { literal } => Pattern::Literal(LiteralNode::new_number(literal)),
+            TokenType::String { literal } => Pattern::Literal(LiteralNode::new_string(literal)),
+            TokenType::Boolean { literal } => Pattern::Literal(LiteralNode::new_boolean(literal)),
+            TokenType::Hempty => Pattern::Literal(LiteralNode::new_hempty()),
+            TokenType::Type => {
+                self.advance("pattern expecting type name")?;
+                // list and range are keywords so the name is taken from the lexeme
+                let name = self.token.lexeme.clone();
+                if !Value::TYPES.contains(&name.as_str()) {
+                    return Err(error(
+                        &self.token.span,
+                        format!(
+                            "unknown type {name}, expected one of {}",
+                            Value::TYPES.join(", ")
+                        ),
+                    ));
+                }
+                Pattern::Type(name)
+            }
+            TokenType::LeftBracket => {
+                let car = self.pattern_name("[")?;
+                self.advance("pattern expecting dot")?;
+                if self.token.token_type != TokenType::Dot {
+                    return Err(error(
+                        &self.token.span,
+                        format!(
+                            ". expected between the names of a pair pattern, found {}",
+                            self.token.token_type
+                        ),
+                    ));
+                }
+                let cdr = self.pattern_name(".")?;
+                self.advance("pattern expecting right bracket")?;
+                if self.token.token_type != TokenType::RightBracket {
+                    return Err(error(
+                        &self.token.span,
+                        format!(
+                            "] expected after a pair pattern, found {}",
+                            self.token.token_type
+                        ),
+                    ));
+                }
+                Pattern::Cons { car, cdr }
+            }
+            tokentype => {
+                return Err(error(
+                    &self.token.span,
+                    format!("pattern expected in match, found {tokentype}"),
+                ));
+            }
+        })
+    }
+
+    /// the name a pair pattern gives to the car or cdr, it can be used in the body of the arm
+    fn pattern_name(&mut self, after: &str) -> Result<String, UmplError> {
+        self.advance("pattern_name")?;
+        let TokenType::Identifier { name } = self.token.token_type.clone() else {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "identifier expected after \"{after}\" in a pair pattern, found {}",
+                    self.token.token_type
+                ),
+            ));
+        };
+        self.variables.push(name.clone());
+        Ok(name)
+    }
+
+    /// parses an if, match, try, loop, create or list where a value is expected, like in a call
+    /// or after return, the code in it is written the same way as outside of any parentheses
+    fn statement_value(&mut self) -> Result<Ast, UmplError> {
+        let paren_count = std::mem::take(&mut self.paren_count);
+        let statement = match self.token.token_type {
+            TokenType::If => self.if_statement(),
+            TokenType::Loop => self.loop_statement(),
+            TokenType::Create => self.create(),
+            TokenType::Match => self.match_statement(),
+            TokenType::Try => self.try_statement(),
+            _ => self.list_declaration(),
+        };
+        self.paren_count = paren_count;
+        statement
+    }
+
+    /// parses the `{condition} ⧼body⧽` after an if or else if
+    fn if_branch(&mut self) -> Result<IfBranch, UmplError> {
+        self.advance("if_branch after if expecting left brace")?;
+        if self.token.token_type != TokenType::LeftBrace {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "{{ expected after \"if\" found TokenType::{:?}",
+                    self.token.token_type
+                ),
+            ));
         }
+        info!("if statement");
+        self.advance("if_branch finding condition")?;
+        let condition: Ast = match self.token.clone().token_type {
+            TokenType::Boolean { literal } => self.boolean(literal),
+            TokenType::LeftParen => match self.after_left_paren()? {
+                Callorexpression::Expression(thing) => thing,
+                Callorexpression::Call(_) => {
+                    return Err(error(&self.token.span, "call found expected expression"));
+                }
+            },
+            TokenType::Identifier { name } => Ast::Identifier(self.var(name)?),
+            tokentype => {
+                return Err(error(
+                    &self.token.span,
+                    format!(
+                        "boolean expected, in if statement condition found TokenType::{tokentype:?}"
+                    ),
+                ));
+            }
+        };
+        info!("after conditon if statement");
+        self.advance("if_branch expecting right brace")?;
+        if self.token.token_type != TokenType::RightBrace {
+            return Err(error(
+                &self.token.span,
+                "right brace expected after if condition",
+            ));
+        }
+        let body = self.parse_block(BlockType::None)?;
+        Ok(IfBranch::new(condition, body))
+    }
+
+    /// parses `over name with list-or-range` after loop if it is there
+    fn loop_over(&mut self) -> Result<Option<LoopOver>, UmplError> {
+        if self.peek().token_type != TokenType::Over {
+            return Ok(None);
+        }
+        self.advance("loop_over found over")?;
+        self.advance("loop_over looking for variable")?;
+        let TokenType::Identifier { name } = self.token.token_type.clone() else {
+            return Err(error(
+                &self.token.span,
+                format!(
+                    "identifier expected after \"over\", found {}",
+                    self.token.token_type
+                ),
+            ));
+        };
+        self.advance("loop_over looking for with")?;
+        if self.token.token_type != TokenType::With {
+            return Err(error(
+                &self.token.span,
+                format!("with keyword expected, found {}", self.token.token_type),
+            ));
+        }
+        self.advance("loop_over looking for list")?;
+        let items = self.get_value()?;
+        self.variables.push(name.clone());
+        Ok(Some(LoopOver::new(name, items)))
     }
 
-    pub fn advance(&mut self, fn_name: &str) {
-        match self.tokens[self.current_position].token_type {
+    /// parses the label after a break or continue if there is one
+    fn jump_label(&mut self) -> Result<Option<String>, UmplError> {
+        let TokenType::Identifier { name } = self.peek().token_type.clone() else {
+            return Ok(None);
+        };
+        let keyword = self.token.lexeme.clone();
+        self.advance("jump_label found label")?;
+        if self.has_loop_label(&name) {
+            Ok(Some(name))
+        } else {
+            Err(error(
+                &self.token.span,
+                format!("no loop labelled {name} around this {keyword}"),
+            ))
+        }
+    }
+
+    /// the next token without moving to it, past the end of the file it is the final EOF
+    fn peek(&self) -> &Token {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        &self.tokens[(self.current_position + offset).min(self.tokens.len() - 1)]
+    }
+
+    pub fn advance(&mut self, fn_name: &str) -> Result<(), UmplError> {
+        // the EOF was already consumed, so whatever wants more tokens is cut off by the end of the file
+        if self.current_position >= self.tokens.len() {
+            return Err(error(&self.token.span, "unexpected end of file"));
+        }
+        match self.peek().token_type {
             TokenType::Return { .. } => {
-                if self.in_function {
-                    self.token = self.tokens[self.current_position].clone();
+                if self.in_function() {
+                    self.token = self.peek().clone();
                 } else {
-                    error(
-                        self.tokens[self.current_position].line,
+                    return Err(error(
+                        &self.peek().span,
                         "Return statement outside of function",
-                    );
+                    ));
                 }
             }
             TokenType::Break | TokenType::Continue => {
-                if self.in_loop {
-                    self.token = self.tokens[self.current_position].clone();
+                if self.in_loop() {
+                    self.token = self.peek().clone();
                 } else {
-                    error(
-                        self.tokens[self.current_position].line,
+                    return Err(error(
+                        &self.peek().span,
                         "Break or continue statement outside of loop",
-                    );
+                    ));
                 }
             }
             TokenType::EOF => {
                 self.done = true;
-                self.token = self.tokens[self.current_position].clone();
+                self.token = self.peek().clone();
+                if self.paren_count != 0 {
+                    self.paren_count = 0;
+                    return Err(error(
+                        &self.token.span,
+                        "unclosed left parenthesis, expected right parenthesis before end of file",
+                    ));
+                }
             }
             TokenType::LeftParen => {
                 self.paren_count += 1;
-                self.token = self.tokens[self.current_position].clone();
+                self.token = self.peek().clone();
             }
             TokenType::RightParen => {
                 if self.paren_count == 0 {
-                    error(
-                        self.tokens[self.current_position].line,
-                        "unmatched right parenthesis",
-                    );
+                    return Err(error(&self.peek().span, "unmatched right parenthesis"));
                 }
                 self.paren_count -= 1;
                 if self.paren_count == 0
-                    && !(vec![TokenType::GreaterThanSymbol, TokenType::LessThanSymbol]
-                        .contains(&self.tokens[self.current_position + 1].token_type))
+                    && !([TokenType::GreaterThanSymbol, TokenType::LessThanSymbol]
+                        .contains(&self.peek_at(1).token_type))
                 {
-                    error(
-                        self.tokens[self.current_position].line,
+                    return Err(error(
+                        &self.peek().span,
                         format!(
                             "greater than symbol (>) or less than symbol (<) expected found {}",
-                            self.tokens[self.current_position + 1].token_type
+                            self.peek_at(1).token_type
                         )
                         .as_str(),
-                    )
+                    ));
                 }
-                self.token = self.tokens[self.current_position].clone();
+                self.token = self.peek().clone();
             }
             TokenType::CodeBlockBegin => {
                 self.weird_bracket_count += 1;
-                self.token = self.tokens[self.current_position].clone();
+                self.token = self.peek().clone();
             }
             TokenType::CodeBlockEnd => {
+                if self.weird_bracket_count == 0 {
+                    // skip the ⧽ so parsing can carry on after it
+                    self.current_position += 1;
+                    return Err(error(
+                        &self.tokens[self.current_position - 1].span,
+                        "unmatched ⧽",
+                    ));
+                }
                 self.weird_bracket_count -= 1;
-                self.token = self.tokens[self.current_position].clone();
+                self.token = self.peek().clone();
             }
             TokenType::GreaterThanSymbol | TokenType::LessThanSymbol => {
                 if self.paren_count == 0 {
-                    self.token = self.tokens[self.current_position].clone();
+                    self.token = self.peek().clone();
                 } else {
-                    error(
-                        self.tokens[self.current_position].line,
+                    return Err(error(
+                        &self.peek().span,
                         "greater than symbol (>) or less than symbol (<) not allowed in middle of expression",
-                    );
+                    ));
                 }
             }
             _ => {
-                self.token = self.tokens[self.current_position].clone();
+                self.token = self.peek().clone();
             }
-        };
+        }
         info!("{}", self.paren_count); //
         info!("new token: {} in function {}", self.token, fn_name);
         self.current_position += 1;
+        Ok(())
     }
 
-    pub fn parse(&mut self) -> Vec<Ast> {
+    pub fn parse(&mut self) -> Result<Vec<Ast>, UmplError> {
         let mut program = Vec::new();
         // loop until we have no more self.tokens
         // in the loop, we use parse_from_tokens to parse the next expression
         // and add it to the program tree
         info!("{:?}", self.tokens);
         while !self.done {
-            let expr = self.parse_from_token();
-            if let Some(t) = expr {
-                debug!("{:?}", t);
-                program.push(t);
+            let start = self.current_position;
+            match self.parse_from_token() {
+                Ok(Some(t)) => {
+                    debug!("{t:?}");
+                    program.push(t);
+                }
+                Ok(None) => {}
+                Err(error) => self.recover(error, start),
             }
         }
+        if self.weird_bracket_count != 0 {
+            self.errors.push(
+                Diagnostic::error(&self.token.span, "unclosed code block")
+                    .with_primary_label(&self.token.span, "expected ⧽ before end of file")
+                    .with_note(format!(
+                        "{} ⧼ left without a matching ⧽",
+                        self.weird_bracket_count
+                    )),
+            );
+        }
         info!("Done parsing");
-        program
+        if self.errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(UmplError::Diagnostics(std::mem::take(&mut self.errors)))
+        }
+    }
+
+    /// the warnings found so far, like a match without else, they do not stop the program from running
+    pub fn take_warnings(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// records the error and skips ahead to the next statement boundary so parsing can continue
+    fn recover(&mut self, error: UmplError, start: usize) {
+        match error {
+            UmplError::Diagnostic(diagnostic) => self.errors.push(diagnostic),
+            UmplError::Diagnostics(diagnostics) => self.errors.extend(diagnostics),
+            UmplError::Exit(_) => {}
+        }
+        // a new statement is not part of the broken one so parse it again
+        if self.current_position > start + 1 && is_statement_start(&self.token.token_type) {
+            self.current_position -= 1;
+        } else {
+            self.synchronize();
+        }
+        self.paren_count = 0;
+    }
+
+    fn synchronize(&mut self) {
+        // code blocks inside the broken statement are skipped as a whole,
+        // the skipped brackets are still counted so an unclosed ⧼ is reported at the end of the file
+        let mut depth = 0;
+        while !self.done {
+            match self.peek().token_type {
+                TokenType::EOF => break,
+                TokenType::CodeBlockBegin => {
+                    depth += 1;
+                    self.weird_bracket_count += 1;
+                }
+                TokenType::CodeBlockEnd if depth == 0 => break,
+                TokenType::CodeBlockEnd => {
+                    depth -= 1;
+                    self.weird_bracket_count -= 1;
+                }
+                TokenType::LeftParen => self.paren_count += 1,
+                TokenType::RightParen => self.paren_count = self.paren_count.saturating_sub(1),
+                ref token_type if depth == 0 && is_statement_start(token_type) => break,
+                _ => {}
+            }
+            self.token = self.peek().clone();
+            self.current_position += 1;
+        }
     }
     #[allow(clippy::too_many_lines)]
-    fn parse_from_token(&mut self) -> Option<Ast> {
-        self.advance("parse_from_token");
+    fn parse_from_token(&mut self) -> Result<Option<Ast>, UmplError> {
+        self.advance("parse_from_token")?;
         info!("new iteration");
         if self.tokens.is_empty() {
-            error(0, "no self.tokens found");
+            return Err(error(&self.token.span, "no self.tokens found"));
         }
         if self.done {
-            return None;
+            return Ok(None);
         }
         info!("PARSEfromTOKEN {}", self.token);
-        match self.token.token_type.clone() {
-            TokenType::LeftParen => match self.after_left_paren() {
+        Ok(match self.token.token_type.clone() {
+            TokenType::LeftParen => match self.after_left_paren()? {
                 Callorexpression::Expression(e) => Some(e),
-                _ => error(self.token.line, "expected expression"),
+                _ => return Err(error(&self.token.span, "expected expression")),
             },
             TokenType::CodeBlockEnd => None,
             TokenType::CodeBlockBegin => {
-                let block = self.parse_block_without_begin(BlockType::None);
-                return Some(Ast::Block(block.clone()));
+                let block = self.parse_block_without_begin(BlockType::None)?;
+                Some(Ast::Block(block))
             }
             TokenType::Identifier { .. } => {
-                error(self.token.line, "variable not allowed in this context");
+                return Err(error(
+                    &self.token.span,
+                    "variable not allowed in this context",
+                ));
             }
             keyword if crate::KEYWORDS.is_keyword(&keyword) => {
                 info!("found keyword {}", self.token.token_type);
                 match self.token.token_type.clone() {
                     TokenType::Potato => {
-                        let start_line = self.token.line;
-                        self.advance("parse_from_token after function looking for function name");
+                        let start_span = self.token.span.clone();
+                        self.advance("parse_from_token after function looking for function name")?;
                         match self.token.token_type.clone() {
                             TokenType::FunctionIdentifier { name, path } if path.is_empty() => {
                                 info!("function identifier found");
-                                self.advance("parse_from_token after function name looking for function arguments");
-                                // check if the next token is a number and save it in a vairable num_args
-                                let num_of_args_and_extra: (f64, bool) = match self.token.token_type
-                                {
-                                    TokenType::Number { literal } => {
-                                        if literal.trunc() == literal {
-                                            self.advance("parse_from_token found number or args looking for function body");
-                                            if self.token.token_type == TokenType::Star {
-                                                self.advance("parse_from_token found star looking for function body");
-                                                (literal, true)
-                                            } else {
-                                                (literal, false)
-                                            }
-                                        } else {
-                                            error(
-                                                self.token.line,
-                                                format!("number expected in function declaration found floating point number literal with {literal}"),
-                                            );
-                                        }
-                                    }
-                                    TokenType::Star => {
-                                        self.advance(
-                                            "parse_from_token found star looking for function body",
-                                        );
-                                        (0.0, true)
-                                    }
-                                    TokenType::CodeBlockBegin => (0.0, false),
-                                    _ => {
-                                        error(
-                                            self.token.line,
-                                            format!("number expected after function identifier, found {}", self.token),
-                                        );
-                                    }
-                                };
-                                let function = self.parse_block_without_begin(BlockType::Function);
+                                self.advance("parse_from_token after function name looking for function arguments")?;
+                                let (num_arguments, extra_arguments) = self.function_arguments()?;
+                                let function =
+                                    self.parse_block_without_begin(BlockType::Function)?;
                                 info!("int function declaration before code block");
-                                debug!("new function {:?}", function);
+                                debug!("new function {function:?}");
                                 Some(Ast::Function(Function::new(
                                     FunctionNode::new(
                                         name,
-                                        num_of_args_and_extra.0,
-                                        num_of_args_and_extra.1,
+                                        num_arguments,
+                                        extra_arguments,
                                         function,
                                     ),
-                                    start_line,
-                                    self.token.line,
-                                    self.filename.clone(),
+                                    self.span_from(&start_span),
                                 )))
                             }
                             tokentype => {
-                                error(
-                                    self.token.line,
+                                return Err(error(
+                                    &self.token.span,
                                     format!("function identifier expected after \"potato\", found TokenType::{tokentype:?}"),
-                                );
+                                ));
                             }
                         }
                     }
-                    TokenType::List => {
-                        self.advance("parse_from_token");
-                        match self.token.token_type.clone() {
-                            TokenType::Identifier { name } => {
-                                info!("list identifier found");
-                                self.advance("parse_from_token");
-                                if self.token.token_type == TokenType::With {
-                                    self.advance("parse_from_token");
-                                    if self.token.token_type == TokenType::LeftBracket {
-                                        self.advance("parse_from_token");
-                                        info!("list with");
-                                        let thing: Ast = self.parse_to_other_stuff();
-                                        self.advance("parse_from_token");
-                                        let thing1: Ast = self.parse_to_other_stuff();
-                                        self.advance("  ");
-                                        if self.token.token_type == TokenType::RightBracket {
-                                            self.variables.push(name.clone());
-                                            Some(Ast::Declaration(Declaration::new(
-                                                DeclarationNode::new(
-                                                    name,
-                                                    &[thing, thing1],
-                                                    self.token.line,
-                                                ),
-                                                self.token.line,
-                                                // TODO: use constructer for located without
-                                                // endline
-                                                self.token.line,
-                                                self.filename.clone(),
-                                            )))
-                                        } else {
-                                            error(
-                                                self.token.line,
-                                                format!(
-                                                    "right bracket expected after list, found {}",
-                                                    self.token.token_type
-                                                )
-                                                .as_str(),
-                                            );
-                                        }
-                                    } else {
-                                        error(
-                                            self.token.line,
-                                            format!(
-                                                "left bracket expected after \"with\", found {}",
-                                                self.token.token_type
-                                            )
-                                            .as_str(),
-                                        );
-                                    }
-                                } else {
-                                    error(
-                                        self.token.line,
-                                        format!(
-                                            "with keyword expected, found TokenType::{:?}",
-                                            self.token.token_type
-                                        )
-                                        .as_str(),
-                                    );
-                                }
-                            }
-                            tokentype => {
-                                error(
-                                    self.tokens[1].line,
-                                    format!(
-                                        "identifier expected, after \"list\" found TokenType::{tokentype:?}"
-                                    )
-                                    .as_str(),
-                                );
-                            }
-                        }
-                    }
-                    TokenType::Create => {
-                        self.advance("parse_from_token");
-                        match self.token.token_type.clone() {
-                            TokenType::Identifier { name } => {
-                                info!("create identifier found");
-                                self.advance("parse_from_token");
-                                if self.token.token_type == TokenType::With {
-                                    self.advance("parse_from_token");
-                                    info!("create identifier with {}", self.token.token_type);
-                                    let thing = self.get_value();
-                                    self.variables.push(name.clone());
-                                    Some(Ast::Declaration(Declaration::new(
-                                        DeclarationNode::new(name, &[thing], self.token.line),
-                                        self.token.line,
-                                        // TODO: use constructer for located without
-                                        // endline
-                                        self.token.line,
-                                        self.filename.clone(),
-                                    )))
-                                } else {
-                                    error(
-                                        self.token.line,
-                                        format!(
-                                            "with keyword expected, found TokenType::{:?}",
-                                            self.token.token_type
-                                        )
-                                        .as_str(),
-                                    );
-                                }
+                    TokenType::List => Some(self.list_declaration()?),
+                    TokenType::Create => Some(self.create()?),
+                    TokenType::Loop => Some(self.loop_statement()?),
+                    TokenType::If => Some(self.if_statement()?),
+                    TokenType::Match => Some(self.match_statement()?),
+                    TokenType::Try => Some(self.try_statement()?),
+                    TokenType::Private => {
+                        let start_span = self.token.span.clone();
+                        match self.parse_from_token()? {
+                            Some(Ast::Function(mut function)) => {
+                                function.node.private = true;
+                                function.span = start_span.to(&function.span);
+                                Some(Ast::Function(function))
                             }
-                            tokentype => {
-                                error(
-                                    self.token.line,
-                                    format!(
-                                        "identifier expected after \"create\", found TokenType::{tokentype:?}"
-                                    )
-                                        .as_str(),
-                                );
+                            Some(Ast::Declaration(mut declaration)) => {
+                                declaration.node.private = true;
+                                declaration.span = start_span.to(&declaration.span);
+                                Some(Ast::Declaration(declaration))
                             }
-                        }
-                    }
-                    TokenType::Loop => {
-                        info!("loop found");
-                        let start_line = self.token.line;
-                        let loop_body = self.parse_block(BlockType::Loop);
-                        info!("Done parsing loop body");
-                        self.advance("parse_from_token after loop body looking for loop end");
-                        Some(Ast::Loop(Loop::new(
-                            LoopNode::new(loop_body),
-                            start_line,
-                            self.token.line,
-                            self.filename.clone(),
-                        )))
-                    }
-                    TokenType::If => {
-                        let start_line = self.token.line;
-                        self.advance("parse_from_token after if expecting left brace");
-                        if self.token.token_type == TokenType::LeftBrace {
-                            info!("if statement");
-                            self.advance("parse_from_token finding condition");
-                            let thing: Ast = match self.token.clone().token_type {
-                                TokenType::Boolean { literal } => self.boolean(literal),
-                                TokenType::LeftParen => match self.after_left_paren() {
-                                    Callorexpression::Expression(thing) => thing,
-                                    _ => {
-                                        error(self.token.line, "call found expected expression");
-                                    }
-                                },
-                                TokenType::Identifier { name } => Ast::Identifier(self.var(name)),
-                                tokentype => {
-                                    error(
-                                        self.token.line,
-                                        format!(
-                                            "boolean expected, in if statement condition found TokenType::{tokentype:?}"
-                                        )
-                                            .as_str(),
-                                    );
-                                }
-                            };
-                            info!("after conditon if statement");
-                            self.advance("parse_from_token if expecting left brace");
-                            if self.token.token_type == TokenType::RightBrace {
-                                let if_body = self.parse_block(BlockType::None);
-                                self.advance("parse_from_token before else");
-                                if self.token.token_type == TokenType::Else {
-                                    let else_body = self.parse_block(BlockType::None);
-                                    Some(Ast::If(If::new(
-                                        IfNode::new(Box::new(thing), if_body, else_body),
-                                        start_line,
-                                        self.token.line,
-                                        self.filename.clone(),
-                                    )))
-                                } else {
-                                    error(
-                                        self.token.line,
-                                        "else keyword expected after if statement",
-                                    );
-                                }
-                            } else {
-                                error(self.token.line, "right brace expected after if condition");
+                            _ => {
+                                return Err(error(
+                                    &self.token.span,
+                                    "only potatoes and variables can be private",
+                                ));
                             }
-                        } else {
-                            error(
-                                self.token.line,
-                                format!(
-                                    "{{ expected after \"if\" found TokenType::{:?}",
-                                    self.token.token_type
-                                )
-                                .as_str(),
-                            );
                         }
                     }
                     TokenType::Return { .. } => {
-                        if self.tokens[self.current_position].token_type == TokenType::Colon {
-                            self.advance("parse_from_token return expecting expression");
-                            return Some(Ast::Return(Return::new(
+                        let start_span = self.token.span.clone();
+                        if self.peek().token_type == TokenType::Colon {
+                            self.advance("parse_from_token return expecting expression")?;
+                            return Ok(Some(Ast::Return(Return::new(
                                 ReturnNode::new_empty(),
-                                self.token.line,
-                                // TODO: empty end line constructor
-                                self.token.line,
-                                self.filename.clone(),
-                            )));
+                                self.span_from(&start_span),
+                            ))));
                         }
-                        self.advance("parse_from_token return expecting expression");
-                        let thing = self.parse_to_other_stuff();
+                        self.advance("parse_from_token return expecting expression")?;
+                        let thing = self.parse_to_other_stuff()?;
                         Some(Ast::Return(Return::new(
                             ReturnNode::new(thing),
-                            self.token.line,
-                            // TODO: empty end line constructor
-                            self.token.line,
-                            self.filename.clone(),
+                            self.span_from(&start_span),
                         )))
                     }
                     TokenType::Break => {
                         info!("break statement");
-                        Some(Ast::Break(Break::new_single_line(
-                            BreakNode::new(),
-                            self.token.line,
-                            self.filename.clone(),
+                        let start_span = self.token.span.clone();
+                        let label = self.jump_label()?;
+                        let value = if self.peek().token_type == TokenType::With {
+                            self.advance("parse_from_token break with")?;
+                            self.advance("parse_from_token break expecting value")?;
+                            Some(self.parse_to_other_stuff()?)
+                        } else {
+                            None
+                        };
+                        Some(Ast::Break(Break::new(
+                            BreakNode::new(label, value),
+                            self.span_from(&start_span),
                         )))
                     }
                     TokenType::Continue => {
                         info!("continue statement");
-                        Some(Ast::Continue(Continue::new_single_line(
-                            ContinueNode::new(),
-                            self.token.line,
-                            self.filename.clone(),
+                        let start_span = self.token.span.clone();
+                        let label = self.jump_label()?;
+                        Some(Ast::Continue(Continue::new(
+                            ContinueNode::new(label),
+                            self.span_from(&start_span),
                         )))
                     }
                     _ => {
-                        error(
-                            self.token.line,
+                        return Err(error(
+                            &self.token.span,
                             "keyword not allowed in expression before left parenthesis",
-                        );
+                        ));
                     }
                 }
             }
             _ => {
-                error(
-                    self.token.line,
+                return Err(error(
+                    &self.token.span,
                     format!("{:?} not allowed in this context", self.token.token_type),
-                );
+                ));
+            }
+        })
+    }
+
+    /// parses the number of arguments of a potato and an optional * for extra arguments, stopping at the ⧼ of its body
+    fn function_arguments(&mut self) -> Result<(f64, bool), UmplError> {
+        let arguments = match self.token.token_type {
+            TokenType::Number { literal } => {
+                if literal.trunc() != literal {
+                    return Err(error(
+                        &self.token.span,
+                        format!("number expected in function declaration found floating point number literal with {literal}"),
+                    ));
+                }
+                self.advance("function_arguments found number of args looking for function body")?;
+                if self.token.token_type == TokenType::Star {
+                    self.advance("function_arguments found star looking for function body")?;
+                    (literal, true)
+                } else {
+                    (literal, false)
+                }
             }
+            TokenType::Star => {
+                self.advance("function_arguments found star looking for function body")?;
+                (0.0, true)
+            }
+            TokenType::CodeBlockBegin => (0.0, false),
+            _ => {
+                return Err(error(
+                    &self.token.span,
+                    format!(
+                        "number expected after function identifier, found {}",
+                        self.token
+                    ),
+                ));
+            }
+        };
+        if self.token.token_type == TokenType::CodeBlockBegin {
+            Ok(arguments)
+        } else {
+            Err(error(
+                &self.token.span,
+                format!(
+                    "⧼ expected for start of function body, found {}",
+                    self.token
+                ),
+            ))
         }
     }
 
-    fn parse_block(&mut self, kind: BlockType) -> Block {
+    /// parses `potato 1 ⧼...⧽` without a function name, the body is parsed like statements even inside a call
+    fn lambda(&mut self) -> Result<Ast, UmplError> {
+        let start_span = self.token.span.clone();
+        self.advance("lambda looking for function arguments")?;
+        let (num_arguments, extra_arguments) = self.function_arguments()?;
+        let paren_count = std::mem::take(&mut self.paren_count);
+        let body = self.parse_block_without_begin(BlockType::Function);
+        self.paren_count = paren_count;
+        Ok(Ast::Lambda(Lambda::new(
+            LambdaNode::new(num_arguments, extra_arguments, body?),
+            self.span_from(&start_span),
+        )))
+    }
+
+    fn parse_block(&mut self, kind: BlockType) -> Result<Block, UmplError> {
         info!("parsing code block");
-        self.advance("loocking for code block begin");
+        self.advance("loocking for code block begin")?;
 
         if self.token.token_type == TokenType::CodeBlockBegin {
             self.parse_block_without_begin(kind)
         } else {
-            error(
-                self.token.line,
+            Err(error(
+                &self.token.span,
                 "code block begin expected for start of block",
-            )
+            ))
         }
     }
-    fn parse_block_without_begin(&mut self, kind: BlockType) -> Block {
-        let set_value = |this: &mut Self, val| match kind {
-            BlockType::Loop => this.in_loop = val,
-            BlockType::Function => this.in_function = val,
-            _ => (),
-        };
-        let start_line = self.token.line;
+    fn parse_block_without_begin(&mut self, kind: BlockType) -> Result<Block, UmplError> {
+        let in_block = kind != BlockType::None;
+        if in_block {
+            self.blocks.push(kind);
+        }
+        let start_span = self.token.span.clone();
         let mut block: Vec<Ast> = Vec::new();
-        while self.tokens[self.current_position].token_type != TokenType::CodeBlockEnd {
-            set_value(self, true);
-            if let Some(t) = self.parse_from_token() {
-                block.push(t);
+        while !self.done && self.peek().token_type != TokenType::CodeBlockEnd {
+            let start = self.current_position;
+            match self.parse_from_token() {
+                Ok(Some(t)) => block.push(t),
+                Ok(None) => {}
+                Err(error) => self.recover(error, start),
             }
         }
-        self.advance("parse_from_token after block, body looking for block end");
-        set_value(self, false);
-        debug!("new block {:?}", block);
-        Block::new(
+        // an unclosed block is reported once the whole file is parsed
+        let end = if self.done {
+            Ok(())
+        } else {
+            self.advance("parse_from_token after block, body looking for block end")
+        };
+        if in_block {
+            self.blocks.pop();
+        }
+        end?;
+        debug!("new block {block:?}");
+        Ok(Block::new(
             BlockNode::new(block),
-            start_line,
-            self.token.line,
-            self.token.filename.clone(),
-        )
+            self.span_from(&start_span),
+        ))
     }
 
-    fn after_left_paren(&mut self) -> Callorexpression {
-        let start_line = self.token.line;
+    fn after_left_paren(&mut self) -> Result<Callorexpression, UmplError> {
+        let start_span = self.token.span.clone();
         if self.paren_count == 1 {
             info!("found expresssion");
-            self.advance("after_left_paren expression");
-            let mut stuff = self.parse_to_stuff();
-            info!("done parsing expression {}", stuff);
-            self.advance("after left paren expr");
+            self.advance("after_left_paren expression")?;
+            let mut stuff = self.parse_to_stuff()?;
+            info!("done parsing expression {stuff}");
+            self.advance("after left paren expr")?;
             if self.token.token_type == TokenType::RightParen {
                 info!("right paren found");
             } else {
-                error(self.token.line, "right parenthesis expected");
+                return Err(
+                    Diagnostic::error(&self.token.span, "right parenthesis expected")
+                        .with_label(&start_span, "unclosed left parenthesis")
+                        .into(),
+                );
             }
-            self.advance("after left paren expr");
+            self.advance("after left paren expr")?;
             info!("found express");
             let prints = match self.token.token_type {
                 TokenType::GreaterThanSymbol => PrintType::PrintLn,
                 TokenType::LessThanSymbol => PrintType::None,
                 _ => {
-                    error(
-                        self.token.line,
+                    return Err(error(
+                        &self.token.span,
                         "greater than symbol or less than symbol expected",
-                    );
+                    ));
                 }
             };
             let prints = if prints == PrintType::PrintLn {
-                match self.tokens[self.current_position].token_type {
+                match self.peek().token_type {
                     TokenType::GreaterThanSymbol => {
-                        self.advance("after left paren expr");
+                        self.advance("after left paren expr")?;
                         PrintType::Print
                     }
                     _ => prints,
@@ -543,203 +950,294 @@ impl Parser {
             } else {
                 prints
             };
-            warn!("{:?}", prints);
+            warn!("{prints:?}");
             stuff.set_print(prints);
-            Callorexpression::Expression(stuff)
+            Ok(Callorexpression::Expression(stuff))
         } else {
-            self.advance("after left paren");
-            if self.token.token_type == TokenType::New {
-                self.advance("after left paren");
-                match self.token.token_type {
-                    TokenType::FunctionIdentifier { .. } => {}
-                    ref tt => {
-                        error(
-                            self.token.line,
-                            &format!("function identifier expected after new found {tt}"),
-                        );
+            self.advance("after left paren")?;
+            let keyword = if self.token.token_type == TokenType::New {
+                self.advance("after left paren")?;
+                match self.token.token_type.clone() {
+                    TokenType::FunctionIdentifier { .. } => self.token.token_type.clone(),
+                    // a variable holding a function, like a lambda
+                    TokenType::Identifier { name } => TokenType::Identifier {
+                        name: self.var(name)?.node.0,
+                    },
+                    tt => {
+                        return Err(error(
+                            &self.token.span,
+                            format!(
+                                "function identifier or variable expected after new found {tt}"
+                            ),
+                        ));
                     }
                 }
-            }
-            let keyword: TokenType = self.token.token_type.clone();
-            let line: i32 = self.token.line;
-            info!("found call {}", keyword);
-            self.advance("after left paren");
+            } else {
+                if let TokenType::Identifier { name } = &self.token.token_type {
+                    return Err(error(
+                        &self.token.span,
+                        format!("new expected before {name} to call the function it holds"),
+                    ));
+                }
+                self.token.token_type.clone()
+            };
+            info!("found call {keyword}");
+            self.advance("after left paren")?;
             let mut args = Vec::new();
 
             while self.token.token_type != TokenType::RightParen {
                 info!("looking for args");
-                args.push(self.parse_to_stuff());
-                self.advance("after left paren");
+                args.push(self.parse_to_stuff()?);
+                self.advance("after left paren")?;
             }
-            Callorexpression::Call(Call::new(
+            Ok(Callorexpression::Call(Call::new(
                 CallNode::new(keyword, args),
-                start_line,
-                line,
-                self.filename.clone(),
-            ))
+                self.span_from(&start_span),
+            )))
         }
     }
 
-    fn var(&mut self, name: String) -> Identifier {
-        if name.starts_with('$') && self.in_function {
-            if self.tokens[self.current_position].token_type == TokenType::With {
-                error(
-                    self.tokens[self.current_position].line,
-                    "function arguments are immutable",
-                );
-            } else {
-                self.ident(name)
-            }
-        } else if self.tokens[self.current_position].token_type == TokenType::Dot {
-            self.advance("Var");
-            self.advance("Var");
-            if let TokenType::Car | TokenType::Cdr = self.token.token_type {
-                // make a string with the name + . and
-                info!("found dot {}", self.token.token_type);
-                let name: String =
-                    name + "." + &format!("{:?}", self.token.token_type).to_lowercase();
-                self.ident(name)
-            } else {
-                error(self.token.line, "car or Cdr expected after dot");
-            }
-        } else {
-            self.ident(name)
+    fn var(&mut self, name: String) -> Result<Identifier, UmplError> {
+        let start_span = self.token.span.clone();
+        if name.starts_with('$') && self.in_function() && self.peek().token_type == TokenType::With
+        {
+            return Err(error(&self.peek().span, "function arguments are immutable"));
+        }
+        let mut name = name;
+        // x.cdr.car is the car of the cdr of x, a dot not followed by car or cdr is part of a dotted list
+        while self.peek().token_type == TokenType::Dot
+            && matches!(self.peek_at(1).token_type, TokenType::Car | TokenType::Cdr)
+        {
+            self.advance("Var")?;
+            self.advance("Var")?;
+            info!("found dot {}", self.token.token_type);
+            name = name + "." + &format!("{:?}", self.token.token_type).to_lowercase();
         }
+        Ok(self.ident(name, &start_span))
     }
-    fn get_value(&mut self) -> Ast {
-        match self.token.token_type.clone() {
+    fn get_value(&mut self) -> Result<Ast, UmplError> {
+        Ok(match self.token.token_type.clone() {
             TokenType::Number { literal } => self.number(literal),
             TokenType::String { literal } => self.string(literal),
             TokenType::Hempty => self.hempty(),
             TokenType::Boolean { literal } => self.boolean(literal),
-            TokenType::LeftParen => match self.after_left_paren() {
+            TokenType::LeftParen => match self.after_left_paren()? {
                 Callorexpression::Expression(expression) => expression,
-                _ => error(
-                    self.token.line,
-                    "expression expected after left parenthesis, found call",
-                ),
+                _ => {
+                    return Err(error(
+                        &self.token.span,
+                        "expression expected after left parenthesis, found call",
+                    ))
+                }
             },
-            TokenType::Identifier { name } => Ast::Identifier(self.var(name)),
+            TokenType::Identifier { name } => Ast::Identifier(self.var(name)?),
+            TokenType::LeftBracket => self.list()?,
+            TokenType::FunctionIdentifier { path, name } => self.function_identifier(path, name),
+            TokenType::Potato => self.lambda()?,
+            TokenType::Create
+            | TokenType::If
+            | TokenType::Loop
+            | TokenType::List
+            | TokenType::Match
+            | TokenType::Try => self.statement_value()?,
             tokentype => {
-                error(
-                    self.token.line,
+                return Err(error(
+                    &self.token.span,
                     format!("identifier expected, after \"create\" found TokenType::{tokentype:?}")
                         .as_str(),
-                );
+                ));
             }
-        }
+        })
     }
 
-    fn parse_to_stuff(&mut self) -> Ast {
+    fn parse_to_stuff(&mut self) -> Result<Ast, UmplError> {
         info!("parsing stuff");
-        match self.token.token_type.clone() {
+        Ok(match self.token.token_type.clone() {
             TokenType::LeftParen => {
                 info!("found left paren");
                 // self.advance("parse to stuff");
-                match self.after_left_paren() {
+                match self.after_left_paren()? {
                     Callorexpression::Call(call) => Ast::Call(call),
-                    Callorexpression::Expression(a) => error(
-                        self.token.line,
-                        format!("call expected after left parenthesis found {a:?}"),
-                    ),
+                    Callorexpression::Expression(a) => {
+                        return Err(error(
+                            &self.token.span,
+                            format!("call expected after left parenthesis found {a:?}"),
+                        ))
+                    }
                 }
             }
             TokenType::Number { literal } => self.number(literal),
             TokenType::String { literal } => self.string(literal),
             TokenType::Hempty => self.hempty(),
             TokenType::Boolean { literal } => self.boolean(literal),
-            TokenType::Identifier { name } => Ast::Identifier(self.var(name)),
-            TokenType::Create => {
-                todo!("variables created in an expression");
-            }
-            TokenType::If => {
-                todo!("if statements in an expression");
-            }
-            TokenType::Loop => {
-                todo!("loops in an expression");
-            }
-            TokenType::Potato => {
-                todo!("function definitions in an expression");
-            }
-            TokenType::List => {
-                todo!("list definitions in an expression");
-            }
+            TokenType::Identifier { name } => Ast::Identifier(self.var(name)?),
+            TokenType::LeftBracket => self.list()?,
+            TokenType::FunctionIdentifier { path, name } => self.function_identifier(path, name),
+            TokenType::Create
+            | TokenType::If
+            | TokenType::Loop
+            | TokenType::List
+            | TokenType::Match
+            | TokenType::Try => self.statement_value()?,
+            TokenType::Potato => self.lambda()?,
             _ => {
-                error(
-                    self.token.line,
+                return Err(error(
+                    &self.token.span,
                     format!("{:?} not allowed in this context", self.token.token_type),
-                );
+                ));
             }
-        }
+        })
     }
 
     fn number(&self, literal: f64) -> Ast {
-        Ast::Literal(Literal::new_single_line(
+        Ast::Literal(Literal::new(
             LiteralNode::new_number(literal),
-            self.token.line,
-            self.filename.clone(),
+            self.token.span.clone(),
         ))
     }
 
     fn string(&self, literal: String) -> Ast {
-        Ast::Literal(Literal::new_single_line(
+        Ast::Literal(Literal::new(
             LiteralNode::new_string(literal),
-            self.token.line,
-            self.filename.clone(),
+            self.token.span.clone(),
         ))
     }
     fn boolean(&self, literal: bool) -> Ast {
-        Ast::Literal(Literal::new_single_line(
+        Ast::Literal(Literal::new(
             LiteralNode::new_boolean(literal),
-            self.token.line,
-            self.filename.clone(),
+            self.token.span.clone(),
         ))
     }
     fn hempty(&self) -> Ast {
-        Ast::Literal(Literal::new_single_line(
+        Ast::Literal(Literal::new(
             LiteralNode::new_hempty(),
-            self.token.line,
-            self.filename.clone(),
+            self.token.span.clone(),
+        ))
+    }
+    fn function_identifier(&self, path: Vec<String>, name: char) -> Ast {
+        Ast::FunctionIdentifier(FunctionIdentifier::new(
+            FunctionIdentifierNode::new(path, name),
+            self.token.span.clone(),
         ))
     }
-    fn ident(&self, ident: String) -> Identifier {
-        Identifier::new_single_line(
-            IdentifierNode::new(ident),
-            self.token.line,
-            self.filename.clone(),
-        )
+    fn ident(&self, ident: String, start_span: &Span) -> Identifier {
+        Identifier::new(IdentifierNode::new(ident), self.span_from(start_span))
     }
-    fn parse_to_other_stuff(&mut self) -> Ast {
-        match self.token.token_type.clone() {
+
+    /// the span from the start of a node up to and including the current token
+    fn span_from(&self, start_span: &Span) -> Span {
+        start_span.to(&self.token.span)
+    }
+    fn parse_to_other_stuff(&mut self) -> Result<Ast, UmplError> {
+        Ok(match self.token.token_type.clone() {
             TokenType::LeftParen => {
                 // self.advance("parse to other stuff");
-                match self.after_left_paren() {
+                match self.after_left_paren()? {
                     Callorexpression::Expression(expression) => expression,
-                    _ => error(
-                        self.token.line,
-                        "expression expected after left parenthesis, found call",
-                    ),
+                    _ => {
+                        return Err(error(
+                            &self.token.span,
+                            "expression expected after left parenthesis, found call",
+                        ))
+                    }
                 }
             }
             TokenType::Number { literal } => self.number(literal),
             TokenType::String { literal } => self.string(literal),
             TokenType::Hempty => self.hempty(),
             TokenType::Boolean { literal } => self.boolean(literal),
-            TokenType::Identifier { name } => Ast::Identifier(self.var(name)),
+            TokenType::Identifier { name } => Ast::Identifier(self.var(name)?),
+            TokenType::LeftBracket => self.list()?,
+            TokenType::FunctionIdentifier { path, name } => self.function_identifier(path, name),
+            TokenType::Potato => self.lambda()?,
+            TokenType::Create
+            | TokenType::If
+            | TokenType::Loop
+            | TokenType::List
+            | TokenType::Match
+            | TokenType::Try => self.statement_value()?,
             _ => {
-                error(
-                    self.token.line,
+                return Err(error(
+                    &self.token.span,
                     format!("{:?} not allowed in this context", self.token.token_type),
-                );
+                ));
+            }
+        })
+    }
+
+    /// parses a list literal starting at the [, `[a b c]` is a list of pairs ending with hempty,
+    /// `[a . b]` is a single pair and `[]` is hempty
+    fn list(&mut self) -> Result<Ast, UmplError> {
+        let start_span = self.token.span.clone();
+        // inside a call the items are calls (one paren), outside they are expressions
+        let in_call = self.paren_count > 0;
+        let mut items = Vec::new();
+        let mut tail = None;
+        self.advance("list")?;
+        while self.token.token_type != TokenType::RightBracket {
+            match self.token.token_type {
+                TokenType::EOF => {
+                    return Err(Diagnostic::error(
+                        &self.token.span,
+                        "right bracket expected before end of file",
+                    )
+                    .with_label(&start_span, "list starts here")
+                    .into());
+                }
+                TokenType::Dot if items.is_empty() => {
+                    return Err(error(
+                        &self.token.span,
+                        "expected a value before the . in a list",
+                    ));
+                }
+                TokenType::Dot => {
+                    self.advance("list tail")?;
+                    tail = Some(Box::new(self.list_item(in_call)?));
+                    self.advance("list tail")?;
+                    if self.token.token_type != TokenType::RightBracket {
+                        return Err(Diagnostic::error(
+                            &self.token.span,
+                            format!(
+                                "right bracket expected after the value following the ., found {}",
+                                self.token.token_type
+                            ),
+                        )
+                        .with_label(&start_span, "list starts here")
+                        .into());
+                    }
+                }
+                _ => {
+                    items.push(self.list_item(in_call)?);
+                    self.advance("list")?;
+                }
             }
         }
+        if items.is_empty() {
+            return Ok(Ast::Literal(Literal::new(
+                LiteralNode::new_hempty(),
+                self.span_from(&start_span),
+            )));
+        }
+        Ok(Ast::List(List::new(
+            ListNode::new(items, tail),
+            self.span_from(&start_span),
+        )))
+    }
+
+    fn list_item(&mut self, in_call: bool) -> Result<Ast, UmplError> {
+        if in_call {
+            self.parse_to_stuff()
+        } else {
+            self.parse_to_other_stuff()
+        }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub enum BlockType {
     None,
-    Loop,
+    /// a loop with its label
+    Loop(Option<String>),
     Function,
 }
 #[derive(PartialEq, Clone)]
@@ -747,3 +1245,38 @@ pub enum Callorexpression {
     Call(Call),
     Expression(Ast),
 }
+
+/// a match needs no else when its arms already fit every value, like `type number` and so on
+/// for every type, a pair pattern covers lists and true and false cover booleans
+fn is_exhaustive(arms: &[MatchArm]) -> bool {
+    let covers = |name: &str| {
+        arms.iter().any(|arm| match &arm.pattern {
+            Pattern::Type(type_name) => type_name == name,
+            Pattern::Cons { .. } => name == "list",
+            Pattern::Literal(LiteralNode::Hempty) => name == "hempty",
+            Pattern::Literal(_) => false,
+        })
+    };
+    let has_boolean = |boolean: bool| {
+        arms.iter()
+            .any(|arm| arm.pattern == Pattern::Literal(LiteralNode::Boolean(boolean)))
+    };
+    Value::TYPES
+        .iter()
+        .all(|name| covers(name) || (*name == "boolean" && has_boolean(true) && has_boolean(false)))
+}
+
+/// the keywords that begin a statement, the parser resynchronizes on these after an error
+const fn is_statement_start(token_type: &TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::Create
+            | TokenType::List
+            | TokenType::Potato
+            | TokenType::Loop
+            | TokenType::If
+            | TokenType::Match
+            | TokenType::Try
+            | TokenType::Private
+    )
+}
@@ -1,4 +1,7 @@
-use crate::{error, token::TokenType};
+use crate::{
+    error::{error, UmplError},
+    token::{Span, TokenType},
+};
 use std::fmt::{self, Debug, Display, Write};
 
 #[derive(PartialEq, Clone, Debug)]
@@ -14,21 +17,52 @@ pub enum Ast {
     Call(Call),
     Declaration(Declaration),
     Block(Block),
+    List(List),
+    FunctionIdentifier(FunctionIdentifier),
+    Lambda(Lambda),
+    Match(Match),
+    Try(Try),
 }
 impl Ast {
     pub(crate) fn set_print(&mut self, prints: PrintType) {
         match self {
-            Ast::Identifier(node) => node.print = prints,
-            Ast::Function(node) => node.print = prints,
-            Ast::If(node) => node.print = prints,
-            Ast::Loop(node) => node.print = prints,
-            Ast::Break(node) => node.print = prints,
-            Ast::Continue(node) => node.print = prints,
-            Ast::Return(node) => node.print = prints,
-            Ast::Literal(node) => node.print = prints,
-            Ast::Call(node) => node.print = prints,
-            Ast::Declaration(node) => node.print = prints,
-            Ast::Block(node) => node.print = prints,
+            Self::Identifier(node) => node.print = prints,
+            Self::Function(node) => node.print = prints,
+            Self::If(node) => node.print = prints,
+            Self::Loop(node) => node.print = prints,
+            Self::Break(node) => node.print = prints,
+            Self::Continue(node) => node.print = prints,
+            Self::Return(node) => node.print = prints,
+            Self::Literal(node) => node.print = prints,
+            Self::Call(node) => node.print = prints,
+            Self::Declaration(node) => node.print = prints,
+            Self::Block(node) => node.print = prints,
+            Self::List(node) => node.print = prints,
+            Self::FunctionIdentifier(node) => node.print = prints,
+            Self::Lambda(node) => node.print = prints,
+            Self::Match(node) => node.print = prints,
+            Self::Try(node) => node.print = prints,
+        }
+    }
+
+    pub const fn span(&self) -> &Span {
+        match self {
+            Self::Identifier(node) => &node.span,
+            Self::Function(node) => &node.span,
+            Self::If(node) => &node.span,
+            Self::Loop(node) => &node.span,
+            Self::Break(node) => &node.span,
+            Self::Continue(node) => &node.span,
+            Self::Return(node) => &node.span,
+            Self::Literal(node) => &node.span,
+            Self::Call(node) => &node.span,
+            Self::Declaration(node) => &node.span,
+            Self::Block(node) => &node.span,
+            Self::List(node) => &node.span,
+            Self::FunctionIdentifier(node) => &node.span,
+            Self::Lambda(node) => &node.span,
+            Self::Match(node) => &node.span,
+            Self::Try(node) => &node.span,
         }
     }
 }
@@ -36,45 +70,63 @@ impl Ast {
 impl Display for Ast {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Ast::Identifier(node) => write!(f, "{node}"),
-            Ast::Function(node) => write!(f, "{node}"),
-            Ast::If(node) => write!(f, "{node}"),
-            Ast::Loop(node) => write!(f, "{node}"),
-            Ast::Break(node) => write!(f, "{node}"),
-            Ast::Continue(node) => write!(f, "{node}"),
-            Ast::Return(node) => write!(f, "{node}"),
-            Ast::Literal(node) => write!(f, "{node}"),
-            Ast::Call(node) => write!(f, "{node}"),
-            Ast::Declaration(node) => write!(f, "{node}"),
-            Ast::Block(node) => write!(f, "{node}"),
+            Self::Identifier(node) => write!(f, "{node}"),
+            Self::Function(node) => write!(f, "{node}"),
+            Self::If(node) => write!(f, "{node}"),
+            Self::Loop(node) => write!(f, "{node}"),
+            Self::Break(node) => write!(f, "{node}"),
+            Self::Continue(node) => write!(f, "{node}"),
+            Self::Return(node) => write!(f, "{node}"),
+            Self::Literal(node) => write!(f, "{node}"),
+            Self::Call(node) => write!(f, "{node}"),
+            Self::Declaration(node) => write!(f, "{node}"),
+            Self::Block(node) => write!(f, "{node}"),
+            Self::List(node) => write!(f, "{node}"),
+            Self::FunctionIdentifier(node) => write!(f, "{node}"),
+            Self::Lambda(node) => write!(f, "{node}"),
+            Self::Match(node) => write!(f, "{node}"),
+            Self::Try(node) => write!(f, "{node}"),
         }
     }
 }
 
+/// breaks out of the innermost loop, or the loop with the label if there is one,
+/// the value is what the loop evaluates to when it is used as a value
 #[derive(PartialEq, Clone, Debug)]
-pub struct BreakNode();
+pub struct BreakNode(pub Option<String>, pub Option<Box<Ast>>);
 impl Display for BreakNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "break")
+        write!(f, "break")?;
+        if let Some(ref label) = self.0 {
+            write!(f, " {label}")?;
+        }
+        if let Some(ref value) = self.1 {
+            write!(f, " with {value}")?;
+        }
+        Ok(())
     }
 }
 impl BreakNode {
-    pub fn new() -> Self {
-        Self()
+    pub fn new(label: Option<String>, value: Option<Ast>) -> Self {
+        Self(label, value.map(Box::new))
     }
 }
 pub type Break = Located<BreakNode>;
-#[derive(PartialEq, Clone, Debug)]
-pub struct ContinueNode();
+/// goes to the next iteration of the innermost loop, or the loop with the label if there is one
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ContinueNode(pub Option<String>);
 
 impl Display for ContinueNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "continue")
+        match self.0 {
+            Some(ref label) => write!(f, "continue {label}"),
+            None => write!(f, "continue"),
+        }
     }
 }
 impl ContinueNode {
-    pub fn new() -> Self {
-        Self()
+    pub const fn new(label: Option<String>) -> Self {
+        Self(label)
     }
 }
 pub type Continue = Located<ContinueNode>;
@@ -95,25 +147,23 @@ impl ReturnNode {
         Self(Some(Box::new(value)))
     }
 
-    pub fn new_empty() -> Self {
+    pub const fn new_empty() -> Self {
         Self(None)
     }
 }
 pub type Return = Located<ReturnNode>;
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum PrintType {
     Print,
     PrintLn,
     None,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Located<T: Clone + Debug + Display> {
     pub node: T,
-    pub start_line: i32,
-    pub end_line: Option<i32>,
-    pub filename: String,
+    pub span: Span,
     pub print: PrintType,
 }
 
@@ -124,21 +174,10 @@ impl<T: Clone + Debug + Display> Display for Located<T> {
 }
 
 impl<T: Clone + Debug + Display> Located<T> {
-    pub fn new(node: T, start_line: i32, end_line: i32, filename: String) -> Self {
+    pub const fn new(node: T, span: Span) -> Self {
         Self {
             node,
-            start_line,
-            end_line: Some(end_line),
-            filename,
-            print: PrintType::None,
-        }
-    }
-    pub fn new_single_line(node: T, start_line: i32, filename: String) -> Self {
-        Self {
-            node,
-            start_line,
-            end_line: None,
-            filename,
+            span,
             print: PrintType::None,
         }
     }
@@ -147,7 +186,7 @@ impl<T: Clone + Debug + Display> Located<T> {
 #[derive(PartialEq, Clone, Debug)]
 pub struct BlockNode(pub Vec<Ast>);
 impl BlockNode {
-    pub fn new(block: Vec<Ast>) -> Self {
+    pub const fn new(block: Vec<Ast>) -> Self {
         Self(block)
     }
 }
@@ -167,7 +206,7 @@ impl Display for BlockNode {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct IdentifierNode(pub String);
 pub type Identifier = Located<IdentifierNode>;
 
@@ -182,6 +221,28 @@ impl Display for IdentifierNode {
     }
 }
 
+/// a potato used as a value by its emoji, like the function given to map
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FunctionIdentifierNode {
+    pub path: Vec<String>,
+    pub name: char,
+}
+pub type FunctionIdentifier = Located<FunctionIdentifierNode>;
+
+impl FunctionIdentifierNode {
+    pub const fn new(path: Vec<String>, name: char) -> Self {
+        Self { path, name }
+    }
+}
+impl Display for FunctionIdentifierNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for module in &self.path {
+            write!(f, "{module}$")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub enum LiteralNode {
     Number(f64),
@@ -192,22 +253,22 @@ pub enum LiteralNode {
 
 pub type Literal = Located<LiteralNode>;
 impl LiteralNode {
-    pub fn get_from_ast(ast: &Ast, line: i32) -> Self {
+    pub fn get_from_ast(ast: &Ast, span: &Span) -> Result<Self, UmplError> {
         match ast {
-            Ast::Literal(l) => l.node.clone(),
-            _ => error::error(line, "not a literal"),
+            Ast::Literal(l) => Ok(l.node.clone()),
+            _ => Err(error(span, "not a literal")),
         }
     }
-    // pub fn from_other_stuff(thing: &OtherStuff, line: i32) -> Self {
+    // pub fn from_other_stuff(thing: &OtherStuff, span: &Span) -> Self {
     //     match thing {
     //         OtherStuff::Literal(literal) => literal.literal.clone(),
-    //         _ => error::error(line, "not a literal"),
+    //         _ => error::error(span, "not a literal"),
     //     }
     // }
-    // pub fn from_stuff(thing: &Stuff, line: i32) -> Self {
+    // pub fn from_stuff(thing: &Stuff, span: &Span) -> Self {
     //     match thing {
     //         Stuff::Literal(literal) => literal.literal.clone(),
-    //         _ => error::error(line, "not a literal"),
+    //         _ => error::error(span, "not a literal"),
     //     }
     // }
     pub const fn new_string(string: String) -> Self {
@@ -254,52 +315,27 @@ impl Display for LiteralNode {
         }
     }
 }
-#[derive(PartialEq, Clone, Debug)]
-pub enum DeclarationType {
-    Cons(Box<List>),
-    Variable(Box<Ast>),
-}
-
-impl DeclarationType {
-    pub fn new(thing: &[Ast], line: i32) -> Self {
-        match thing.len() {
-            0 => error::error(line, "expected Identifier, got empty list"),
-            1 => Self::Variable(Box::new(thing[0].clone())),
-            2 => Self::Cons(Box::new(List::new(thing))),
-            _ => error::error(
-                line,
-                "expected Identifier, got list with more than 2 elements",
-            ),
-        }
-    }
-}
-
 #[derive(PartialEq, Clone, Debug)]
 pub struct DeclarationNode {
     pub name: String,
-    pub value: DeclarationType,
+    pub value: Box<Ast>,
+    /// private variables cannot be read from outside their module
+    pub private: bool,
 }
 
 pub type Declaration = Located<DeclarationNode>;
 impl DeclarationNode {
-    pub fn new(name: String, value: &[Ast], line: i32) -> Self {
+    pub fn new(name: String, value: Ast) -> Self {
         Self {
-            value: DeclarationType::new(value, line),
+            value: Box::new(value),
             name,
+            private: false,
         }
     }
 }
 impl Display for DeclarationNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{} {}",
-            self.name,
-            match &self.value {
-                DeclarationType::Cons(list) => format!("cons: {list}"),
-                DeclarationType::Variable(vairable) => format!("variable: {vairable}"),
-            }
-        )
+        write!(f, "{} with: {}", self.name, self.value)
     }
 }
 
@@ -310,7 +346,7 @@ pub struct CallNode {
 }
 
 impl CallNode {
-    pub fn new(keyword: TokenType, arguments: Vec<Ast>) -> Self {
+    pub const fn new(keyword: TokenType, arguments: Vec<Ast>) -> Self {
         Self { keyword, arguments }
     }
 }
@@ -338,15 +374,18 @@ pub struct FunctionNode {
     pub num_arguments: f64,
     pub extra_arguments: bool,
     pub body: Block,
+    /// private functions cannot be called from outside their module
+    pub private: bool,
 }
 
 impl FunctionNode {
-    pub fn new(name: char, num_arguments: f64, extra_arguments: bool, body: Block) -> Self {
+    pub const fn new(name: char, num_arguments: f64, extra_arguments: bool, body: Block) -> Self {
         Self {
             name,
             num_arguments,
             extra_arguments,
             body,
+            private: false,
         }
     }
 }
@@ -363,38 +402,79 @@ impl Display for FunctionNode {
     }
 }
 
+/// a potato without a name, it evaluates to a function that can still see the scope it was made in
 #[derive(PartialEq, Clone, Debug)]
-pub struct List {
-    pub car: Ast,
-    pub cdr: Ast,
+pub struct LambdaNode {
+    pub num_arguments: f64,
+    pub extra_arguments: bool,
+    pub body: Block,
 }
 
-impl List {
-    pub fn new(thing: &[Ast]) -> Self {
+impl LambdaNode {
+    pub const fn new(num_arguments: f64, extra_arguments: bool, body: Block) -> Self {
         Self {
-            car: thing[0].clone(),
-            cdr: thing[1].clone(),
+            num_arguments,
+            extra_arguments,
+            body,
         }
     }
 }
-impl Display for List {
+
+pub type Lambda = Located<LambdaNode>;
+
+impl Display for LambdaNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "with: [{}, {}]", self.car, self.cdr)
+        write!(
+            f,
+            "Lambda with {} arguments and body: [\n\t{}\n]",
+            self.num_arguments, self.body
+        )
+    }
+}
+
+/// a list literal, it becomes pairs of each item and the rest of the list ending with the tail (or hempty)
+#[derive(PartialEq, Clone, Debug)]
+pub struct ListNode {
+    pub items: Vec<Ast>,
+    /// what the last pair points to, from the dotted form `[a b . c]`
+    pub tail: Option<Box<Ast>>,
+}
+
+impl ListNode {
+    pub const fn new(items: Vec<Ast>, tail: Option<Box<Ast>>) -> Self {
+        Self { items, tail }
+    }
+}
+
+pub type List = Located<ListNode>;
+
+impl Display for ListNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items = self
+            .items
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        match &self.tail {
+            Some(tail) => write!(f, "list: [{items} . {tail}]"),
+            None => write!(f, "list: [{items}]"),
+        }
     }
 }
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct IfNode {
-    pub condition: Box<Ast>,
-    pub body_true: Block,
-    pub body_false: Block,
+    /// the if and each else if, only the body of the first one with a true condition runs
+    pub branches: Vec<IfBranch>,
+    /// runs when no condition is true, an if without else does nothing then
+    pub body_false: Option<Block>,
 }
 
 impl IfNode {
-    pub fn new(condition: Box<Ast>, body_true: Block, body_false: Block) -> Self {
+    pub const fn new(branches: Vec<IfBranch>, body_false: Option<Block>) -> Self {
         Self {
-            condition,
-            body_true,
+            branches,
             body_false,
         }
     }
@@ -403,27 +483,188 @@ impl IfNode {
 pub type If = Located<IfNode>;
 
 impl Display for IfNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "if statement:")?;
+        for branch in &self.branches {
+            write!(f, " {branch}")?;
+        }
+        if let Some(ref body_false) = self.body_false {
+            write!(f, " and when false: [\n{body_false}\n]")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct IfBranch {
+    pub condition: Box<Ast>,
+    pub body: Block,
+}
+
+impl IfBranch {
+    pub fn new(condition: Ast, body: Block) -> Self {
+        Self {
+            condition: Box::new(condition),
+            body,
+        }
+    }
+}
+
+impl Display for IfBranch {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "if statement: with condition: [{}] when true: [\n{}\n] and when false: [\n{}\n]",
-            self.condition, self.body_true, self.body_false,
+            "with condition: [{}] when true: [\n{}\n]",
+            self.condition, self.body
         )
     }
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub struct LoopNode(pub Block);
+pub struct MatchNode {
+    pub value: Box<Ast>,
+    /// only the body of the first arm whose pattern fits the value runs
+    pub arms: Vec<MatchArm>,
+    /// runs when no pattern fits, a match without else does nothing then
+    pub default: Option<Block>,
+}
+
+impl MatchNode {
+    pub fn new(value: Ast, arms: Vec<MatchArm>, default: Option<Block>) -> Self {
+        Self {
+            value: Box::new(value),
+            arms,
+            default,
+        }
+    }
+}
+
+pub type Match = Located<MatchNode>;
+
+impl Display for MatchNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "match [{}]:", self.value)?;
+        for arm in &self.arms {
+            write!(f, " {arm}")?;
+        }
+        if let Some(ref default) = self.default {
+            write!(f, " else: [\n{default}\n]")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Block,
+}
+
+impl MatchArm {
+    pub const fn new(pattern: Pattern, body: Block) -> Self {
+        Self { pattern, body }
+    }
+}
+
+impl Display for MatchArm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "when {}: [\n{}\n]", self.pattern, self.body)
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum Pattern {
+    /// a number, string, boolean or hempty that has to be equal to the value
+    Literal(LiteralNode),
+    /// `type name` fits any value whose type is name
+    Type(String),
+    /// `[car . cdr]` fits any list and names its first item and the rest of it
+    Cons { car: String, cdr: String },
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(literal) => write!(f, "{literal}"),
+            Self::Type(name) => write!(f, "type {name}"),
+            Self::Cons { car, cdr } => write!(f, "[{car} . {cdr}]"),
+        }
+    }
+}
+
+/// runs the body, if it errors the rescue runs instead with the error in the variable
+#[derive(PartialEq, Clone, Debug)]
+pub struct TryNode {
+    pub body: Block,
+    /// the variable the message and line of the error are put in
+    pub name: String,
+    pub rescue: Block,
+}
+
+impl TryNode {
+    pub const fn new(body: Block, name: String, rescue: Block) -> Self {
+        Self { body, name, rescue }
+    }
+}
+
+pub type Try = Located<TryNode>;
+
+impl Display for TryNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "try: [\n{}\n] rescue {}: [\n{}\n]",
+            self.body, self.name, self.rescue
+        )
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct LoopNode {
+    pub body: Block,
+    /// the name break and continue can use to leave an outer loop
+    pub label: Option<String>,
+    /// a loop over a list or range runs once for each item and stops by itself
+    pub over: Option<LoopOver>,
+}
 
 impl LoopNode {
-    pub fn new(block: Block) -> Self {
-        Self(block)
+    pub const fn new(body: Block, label: Option<String>, over: Option<LoopOver>) -> Self {
+        Self { body, label, over }
     }
 }
 
 impl Display for LoopNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        if let Some(ref label) = self.label {
+            write!(f, "{label}: ")?;
+        }
+        if let Some(ref over) = self.over {
+            write!(f, "over {over} ")?;
+        }
+        write!(f, "{}", self.body)
+    }
+}
+
+/// the variable each item is put in and the list or range the items come from
+#[derive(PartialEq, Clone, Debug)]
+pub struct LoopOver {
+    pub name: String,
+    pub items: Box<Ast>,
+}
+
+impl LoopOver {
+    pub fn new(name: String, items: Ast) -> Self {
+        Self {
+            name,
+            items: Box::new(items),
+        }
+    }
+}
+
+impl Display for LoopOver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} with {}", self.name, self.items)
     }
 }
 
@@ -1,13 +1,14 @@
 use crate::{
-    error::{self, arg_error},
-    parser::rules::{Ast, LiteralNode},
+    error::{arg_error, error, UmplError},
+    parser::rules::Ast,
+    value::{Range, Value},
 };
 use hexponent::FloatLiteral;
 use std::{
     env::consts::OS,
     fmt::{self, Debug, Display},
     io::{self, Write},
-    process::{exit, Command},
+    process::Command,
 };
 #[derive(PartialEq, Debug, Clone)]
 #[allow(clippy::module_name_repetitions)]
@@ -39,7 +40,7 @@ pub enum TokenType {
     Or,
     Not,
     Identifier { name: String },
-    FunctionIdentifier { path: Vec<char>, name: char },
+    FunctionIdentifier { path: Vec<String>, name: char },
     String { literal: String },
     Number { literal: f64 },
     Create,
@@ -52,14 +53,21 @@ pub enum TokenType {
     List,
     Car,
     Cdr,
+    Cadr,
+    Cddr,
+    Caddr,
     Return { value: Option<Box<Ast>> },
     Colon,
     Break,
     Continue,
     Loop,
+    Over,
     Potato,
     If,
     Else,
+    Match,
+    Try,
+    Rescue,
     Hempty,
     Boolean { literal: bool },
     Input,
@@ -85,346 +93,590 @@ pub enum TokenType {
     CreateFile,
     DeleteFile,
     Type,
+    Length,
+    Nth,
+    Append,
+    Reverse,
+    Last,
+    Contains,
+    Join,
+    Split,
+    Map,
+    Filter,
+    Reduce,
+    Fold,
+    SortBy,
+    Range,
     Module,
+    Private,
 }
 
 impl TokenType {
     #[allow(clippy::too_many_lines)]
-    pub fn r#do(&self, args: &[LiteralNode], line: i32) -> LiteralNode {
-        if crate::KEYWORDS.is_keyword(self) {
-            match self {
-                Self::Not => {
-                    if args.len() != 1 {
-                        error::error(line, "Expected 1 argument for not operator");
-                    }
-                    match &args[0] {
-                        LiteralNode::Boolean(b) => LiteralNode::Boolean(!b),
-                        _ => error::error(line, "Expected boolean for not operator"),
-                    }
+    pub fn r#do(&self, args: &[Value], span: &Span) -> Result<Value, UmplError> {
+        if !crate::KEYWORDS.is_keyword(self) {
+            return Err(error(span, format!("Unknown keyword, {self}")));
+        }
+        Ok(match self {
+            Self::Not => {
+                if args.len() != 1 {
+                    return Err(error(span, "Expected 1 argument for not operator"));
                 }
-                Self::Plus | Self::Minus | Self::Divide | Self::Multiply => {
-                    match &args[0] {
-                        LiteralNode::Number(number) => {
-                            // check if minus and only one argument
-                            let mut total: f64 = if self == &Self::Minus && args.len() == 1 {
-                                -number
-                            } else {
-                                *number
-                            };
-                            for thing in args.iter().skip(1) {
-                                if let LiteralNode::Number(number) = thing {
-                                    {
-                                        // convert the self to an operator
-                                        match self {
-                                            Self::Plus => {
-                                                total += number;
-                                            }
-                                            Self::Minus => {
-                                                total -= number;
-                                            }
-                                            Self::Divide => {
-                                                total /= number;
-                                            }
-                                            Self::Multiply => {
-                                                total *= number;
-                                            }
-                                            _ => {}
-                                        };
+                match &args[0] {
+                    Value::Boolean(b) => Value::Boolean(!b),
+                    _ => return Err(error(span, "Expected boolean for not operator")),
+                }
+            }
+            Self::Plus | Self::Minus | Self::Divide | Self::Multiply => {
+                arg_error(1, args.len() as u32, self, true, span)?;
+                match &args[0] {
+                    Value::Number(number) => {
+                        // check if minus and only one argument
+                        let mut total: f64 = if self == &Self::Minus && args.len() == 1 {
+                            -number
+                        } else {
+                            *number
+                        };
+                        for thing in args.iter().skip(1) {
+                            if let Value::Number(number) = thing {
+                                {
+                                    // convert the self to an operator
+                                    match self {
+                                        Self::Plus => {
+                                            total += number;
+                                        }
+                                        Self::Minus => {
+                                            total -= number;
+                                        }
+                                        Self::Divide => {
+                                            total /= number;
+                                        }
+                                        Self::Multiply => {
+                                            total *= number;
+                                        }
+                                        _ => {}
                                     }
                                 }
                             }
-                            LiteralNode::Number(total)
                         }
-                        LiteralNode::String(string) => {
-                            let mut new_string = string.to_string();
-                            for (index, thing) in args.iter().skip(1).enumerate() {
-                                match self {
-                                    Self::Plus => {
-                                        match thing {
-                                            LiteralNode::String(ref string) => {
-                                                new_string.push_str(string);
-                                            }
-                                            LiteralNode::Number(number) => {
-                                                new_string.push_str(&number.to_string());
-                                            }
-                                            LiteralNode::Boolean(boolean) => {
-                                                new_string.push_str(&boolean.to_string());
-                                            }
-                                            LiteralNode::Hempty => {
-                                                new_string.push_str("HEMPTY");
-                                            }
-                                        };
+                        Value::Number(total)
+                    }
+                    Value::String(string) => {
+                        let mut new_string = string.clone();
+                        for (index, thing) in args.iter().skip(1).enumerate() {
+                            match self {
+                                Self::Plus => match thing {
+                                    Value::String(ref string) => {
+                                        new_string.push_str(string);
                                     }
-                                    Self::Multiply => {
-                                        if index > 0 {
-                                            error::error(
-                                                line,
-                                                "Multiply can only be used with the car argument",
-                                            );
-                                        }
-                                        match thing {
-                                            LiteralNode::Number(number) => {
-                                                let mut new_new_string = String::new();
-                                                for _ in 0..*number as i32 - 1 {
-                                                    new_new_string.push_str(&new_string);
-                                                }
-                                                new_string = new_new_string;
-                                            }
-                                            _ => {
-                                                error::error(
-                                                    line,
-                                                    "strings can only be multiplied by numbers",
-                                                );
+                                    Value::Number(number) => {
+                                        new_string.push_str(&number.to_string());
+                                    }
+                                    Value::Boolean(boolean) => {
+                                        new_string.push_str(&boolean.to_string());
+                                    }
+                                    Value::Hempty => {
+                                        new_string.push_str("HEMPTY");
+                                    }
+                                    Value::File(_)
+                                    | Value::Cons(_)
+                                    | Value::Function(_)
+                                    | Value::Range(_) => {
+                                        return Err(error(
+                                            span,
+                                            format!(
+                                                "cannot add a {} to a string",
+                                                thing.get_type()
+                                            ),
+                                        ));
+                                    }
+                                },
+                                Self::Multiply => {
+                                    if index > 0 {
+                                        return Err(error(
+                                            span,
+                                            "Multiply can only be used with the car argument",
+                                        ));
+                                    }
+                                    match thing {
+                                        Value::Number(number) => {
+                                            let mut new_new_string = String::new();
+                                            for _ in 0..*number as i32 - 1 {
+                                                new_new_string.push_str(&new_string);
                                             }
+                                            new_string = new_new_string;
+                                        }
+                                        _ => {
+                                            return Err(error(
+                                                span,
+                                                "strings can only be multiplied by numbers",
+                                            ));
                                         }
                                     }
-                                    Self::Divide | Self::Minus => {
-                                        error::error(
-                                            line,
-                                            "Only numbers can be divided or subtracted found",
-                                        );
-                                    }
-                                    _ => {}
-                                };
+                                }
+                                Self::Divide | Self::Minus => {
+                                    return Err(error(
+                                        span,
+                                        "Only numbers can be divided or subtracted found",
+                                    ));
+                                }
+                                _ => {}
                             }
-                            LiteralNode::String(new_string)
                         }
-                        _ => error::error(line, "Invalid literal arguments"),
+                        Value::String(new_string)
                     }
+                    _ => return Err(error(span, "Invalid literal arguments")),
                 }
-                Self::Error
-                | Self::Input
-                | Self::StrToBool
-                | Self::StrToHempty
-                | Self::StrToNum
-                | Self::RunCommand => {
-                    arg_error(1, args.len() as u32, self, false, line);
-                    match &args[0] {
-                        LiteralNode::String(ref string) => match self {
-                            Self::Error => exit(1),
-                            Self::Input => {
-                                let mut input = String::new();
-                                print!("{string}");
-                                // flush stdout
-                                io::stdout().flush().unwrap_or_else(|_| {
-                                    error::error(line, "Error flushing stdout");
-                                });
-                                io::stdin().read_line(&mut input).unwrap_or_else(|_| {
-                                    error::error(line, "Failed to read input");
-                                });
-                                LiteralNode::String(input.trim().to_string())
+            }
+            Self::Error
+            | Self::Input
+            | Self::StrToBool
+            | Self::StrToHempty
+            | Self::StrToNum
+            | Self::RunCommand => {
+                arg_error(1, args.len() as u32, self, false, span)?;
+                match &args[0] {
+                    Value::String(ref string) => match self {
+                        Self::Error => return Err(error(span, string)),
+                        Self::Input => {
+                            let mut input = String::new();
+                            print!("{string}");
+                            // flush stdout
+                            io::stdout()
+                                .flush()
+                                .map_err(|_| error(span, "Error flushing stdout"))?;
+                            io::stdin()
+                                .read_line(&mut input)
+                                .map_err(|_| error(span, "Failed to read input"))?;
+                            Value::String(input.trim().to_string())
+                        }
+                        Self::StrToBool => {
+                            if string == "true" {
+                                Value::Boolean(true)
+                            } else if string == "false" {
+                                Value::Boolean(false)
+                            } else {
+                                return Err(error(span, "Expected true or false"));
                             }
-                            Self::StrToBool => {
-                                if string == "true" {
-                                    LiteralNode::Boolean(true)
-                                } else if string == "false" {
-                                    LiteralNode::Boolean(false)
-                                } else {
-                                    error::error(line, "Expected true or false");
-                                }
+                        }
+                        Self::StrToHempty => {
+                            if string == "HEMPTY" {
+                                Value::Hempty
+                            } else {
+                                return Err(error(span, "Expected HEMPTY"));
                             }
-                            Self::StrToHempty => {
-                                if string == "HEMPTY" {
-                                    LiteralNode::Hempty
-                                } else {
-                                    error::error(line, "Expected HEMPTY");
+                        }
+                        Self::StrToNum => {
+                            let string = match string {
+                                strings if string.starts_with("0x") => {
+                                    strings.clone().trim().to_owned()
                                 }
-                            }
-                            Self::StrToNum => {
-                                let string = match string {
-                                    strings if string.starts_with("0x") => {
-                                        strings.clone().trim().to_owned()
-                                    }
-                                    strings => format!("0x{}", strings.trim()),
-                                };
-                                let number: FloatLiteral = string.parse().map_or_else(
-                                    |_| {
-                                        error::error(
-                                            line,
-                                            format!(
-                                                "Error parsing string {} to number",
-                                                string.trim()
-                                            ),
-                                        )
-                                    },
-                                    |value: FloatLiteral| value,
-                                );
-                                LiteralNode::Number(number.convert::<f64>().inner())
-                            }
-                            Self::RunCommand => {
-                                let cmd = if OS == "windows" {
-                                    let mut cmd = Command::new("powershell");
-                                    cmd.args(["-c", string.trim()]).output()
-                                } else {
-                                    let mut cmd = Command::new("sh");
-                                    cmd.args(["-c", string.trim()]).output()
-                                };
-                                let cmd: String = match cmd {
-                                    Ok(value) => {
-                                        if value.status.success() {
-                                            String::from_utf8_lossy(&value.stdout).into()
-                                        } else {
-                                            String::from_utf8_lossy(&value.stderr).into()
-                                        }
+                                strings => format!("0x{}", strings.trim()),
+                            };
+                            let number: FloatLiteral = string.parse().map_err(|_| {
+                                error(
+                                    span,
+                                    format!("Error parsing string {} to number", string.trim()),
+                                )
+                            })?;
+                            Value::Number(number.convert::<f64>().inner())
+                        }
+                        Self::RunCommand => {
+                            let cmd = if OS == "windows" {
+                                let mut cmd = Command::new("powershell");
+                                cmd.args(["-c", string.trim()]).output()
+                            } else {
+                                let mut cmd = Command::new("sh");
+                                cmd.args(["-c", string.trim()]).output()
+                            };
+                            let cmd: String = match cmd {
+                                Ok(value) => {
+                                    if value.status.success() {
+                                        String::from_utf8_lossy(&value.stdout).into()
+                                    } else {
+                                        String::from_utf8_lossy(&value.stderr).into()
                                     }
-                                    Err(_) => error::error(
-                                        line,
+                                }
+                                Err(_) => {
+                                    return Err(error(
+                                        span,
                                         format!("Error running command {}", string.trim()),
-                                    ),
-                                };
-                                LiteralNode::String(cmd)
-                            }
-                            _ => {
-                                error::error(line, "command not found");
-                            }
-                        },
-                        _ => error::error(line, "Expected string for input operator"),
-                    }
+                                    ))
+                                }
+                            };
+                            Value::String(cmd)
+                        }
+                        _ => {
+                            return Err(error(span, "command not found"));
+                        }
+                    },
+                    _ => return Err(error(span, "Expected string for input operator")),
                 }
-                Self::NotEqual | Self::Equal => {
-                    if args.len() != 2 {
-                        error::error(line, format!("Expected 2 arguments for {self:?} operator"));
-                    }
-                    let type_ = &args[0];
-                    let type_1 = &args[1];
-                    if type_.type_eq(type_1) {
-                    } else {
-                        error::error(
-                            line,
+            }
+            Self::NotEqual | Self::Equal => {
+                if args.len() != 2 {
+                    return Err(error(
+                        span,
+                        format!("Expected 2 arguments for {self:?} operator"),
+                    ));
+                }
+                let type_ = &args[0];
+                let type_1 = &args[1];
+                if type_.type_eq(type_1) {
+                } else {
+                    return Err(error(
+                            span,
                             format!(
                                 "{type_} and {type_1} are not the same type which is required for {self} operator"
                             ),
-                        );
-                    }
-                    if self == &Self::Equal {
-                        LiteralNode::Boolean(type_ == type_1)
-                    } else {
-                        LiteralNode::Boolean(!(type_ == type_1))
-                    }
+                        ));
                 }
-                Self::Or | Self::And => {
-                    if args.len() != 2 {
-                        error::error(line, format!("Expected 2 arguments for {self:?} operator"));
-                    }
-                    let bool_1 = match &args[0] {
-                        LiteralNode::Boolean(boolean) => boolean,
-                        _ => error::error(line, format!("Expected boolean for {self:?} operator")),
-                    };
-                    let bool_2 = match &args[1] {
-                        LiteralNode::Boolean(boolean) => boolean,
-                        _ => error::error(line, format!("Expected boolean for {self:?} operator")),
-                    };
-                    if bool_1 == bool_2 {
-                        if bool_1 == &true {
-                            LiteralNode::Boolean(true)
-                        } else {
-                            LiteralNode::Boolean(false)
-                        }
-                    } else {
-                        LiteralNode::Boolean(false)
-                    }
+                if self == &Self::Equal {
+                    Value::Boolean(type_ == type_1)
+                } else {
+                    Value::Boolean(!(type_ == type_1))
                 }
-                Self::GreaterThan | Self::LessThan | Self::GreaterEqual | Self::LessEqual => {
-                    if args.len() != 2 {
-                        error::error(line, format!("Expected 2 arguments for {self:?} operator"));
-                    }
-                    let type_ = match &args[0] {
-                        LiteralNode::Number(number) => number,
-                        _ => error::error(line, format!("Expected number for {self:?} operator")),
-                    };
-                    let type_1 = match &args[1] {
-                        LiteralNode::Number(number) => number,
-                        _ => error::error(line, format!("Expected number for {self:?} operator")),
-                    };
-                    if self == &Self::GreaterThan {
-                        LiteralNode::Boolean(type_ > type_1)
-                    } else if self == &Self::LessThan {
-                        LiteralNode::Boolean(type_ < type_1)
-                    } else if self == &Self::GreaterEqual {
-                        LiteralNode::Boolean(type_ >= type_1)
-                    } else {
-                        LiteralNode::Boolean(type_ <= type_1)
-                    }
+            }
+            Self::Or | Self::And => {
+                if args.len() != 2 {
+                    return Err(error(
+                        span,
+                        format!("Expected 2 arguments for {self:?} operator"),
+                    ));
                 }
-                Self::Exit => {
-                    if args.len() == 1 {
-                        match &args[0] {
-                            LiteralNode::Number(number) => exit(*number as i32),
-                            _ => {
-                                error::error(line, format!("Expected number for {self:?} operator"))
-                            }
-                        }
+                let Value::Boolean(bool_1) = &args[0] else {
+                    return Err(error(
+                        span,
+                        format!("Expected boolean for {self:?} operator"),
+                    ));
+                };
+                let Value::Boolean(bool_2) = &args[1] else {
+                    return Err(error(
+                        span,
+                        format!("Expected boolean for {self:?} operator"),
+                    ));
+                };
+                if bool_1 == bool_2 {
+                    if bool_1 == &true {
+                        Value::Boolean(true)
                     } else {
-                        error::error(line, format!("Expected 1 argument for {self:?} operator"));
+                        Value::Boolean(false)
                     }
+                } else {
+                    Value::Boolean(false)
                 }
-                Self::SplitOn => {
-                    if args.len() < 2 {
-                        error::error(
-                            line,
-                            format!("Expected al least 2 arguments for {self:?} operator"),
-                        );
-                    }
-                    let og_string = match &args[0] {
-                        LiteralNode::String(string) => string,
-                        _ => error::error(line, format!("Expected string for {self:?} operator")),
-                    };
-                    let split_on = match &args[1] {
-                        LiteralNode::String(string) => string,
-                        _ => error::error(line, format!("Expected string for {self:?} operator")),
-                    };
-                    // check if there is a third argument (number)
-                    args.get(2).map_or_else(
-                        || {
-                            og_string.split_once(split_on).map_or_else(
-                                || LiteralNode::String(og_string.to_string()),
-                                |v| LiteralNode::String(v.0.to_string()),
-                            )
-                        },
-                        |number| -> LiteralNode {
-                            if let LiteralNode::Number(number) = number {
-                                let number = *number as usize;
-                                // return the string until the nth time split_on is found
-                                let string: Vec<&str> =
-                                    og_string.split_inclusive(split_on).collect::<Vec<&str>>();
-                                if number > string.len() {
-                                    error::error(
-                                        line,
-                                        format!("{number} is greater than the number of splits"),
-                                    );
-                                }
-                                // loop through the splits and add them to the string if they are less than the number
-                                let mut ret_string = String::new();
-                                string.iter().take(number).for_each(|i: &&str| {
-                                    ret_string.push_str(i);
-                                });
-                                let ret_string = ret_string
-                                    .rsplit_once(split_on)
-                                    .map_or(og_string.to_string(), |string| string.0.to_string());
-                                LiteralNode::String(ret_string)
-                            } else {
-                                error::error(line, format!("Expected number for {self:?} operator"))
-                            }
-                        },
+            }
+            Self::GreaterThan | Self::LessThan | Self::GreaterEqual | Self::LessEqual => {
+                if args.len() != 2 {
+                    return Err(error(
+                        span,
+                        format!("Expected 2 arguments for {self:?} operator"),
+                    ));
+                }
+                let Value::Number(type_) = &args[0] else {
+                    return Err(error(
+                        span,
+                        format!("Expected number for {self:?} operator"),
+                    ));
+                };
+                let Value::Number(type_1) = &args[1] else {
+                    return Err(error(
+                        span,
+                        format!("Expected number for {self:?} operator"),
+                    ));
+                };
+                if self == &Self::GreaterThan {
+                    Value::Boolean(type_ > type_1)
+                } else if self == &Self::LessThan {
+                    Value::Boolean(type_ < type_1)
+                } else if self == &Self::GreaterEqual {
+                    Value::Boolean(type_ >= type_1)
+                } else {
+                    Value::Boolean(type_ <= type_1)
+                }
+            }
+            Self::Car | Self::Cdr | Self::Cadr | Self::Cddr | Self::Caddr => {
+                arg_error(1, args.len() as u32, self, false, span)?;
+                // the accessors in the order they are applied, cadr is the car of the cdr
+                let accessors = match self {
+                    Self::Car => "a",
+                    Self::Cdr => "d",
+                    Self::Cadr => "da",
+                    Self::Cddr => "dd",
+                    _ => "dda",
+                };
+                accessors
+                    .chars()
+                    .try_fold(args[0].clone(), |value, accessor| {
+                        let cons = value.get_cons(span, self)?;
+                        let cons = cons.borrow();
+                        Ok::<_, UmplError>(if accessor == 'a' {
+                            cons.car.clone()
+                        } else {
+                            cons.cdr.clone()
+                        })
+                    })?
+            }
+            Self::Length | Self::Reverse | Self::Last => {
+                arg_error(1, args.len() as u32, self, false, span)?;
+                let items = args[0].to_vec(span, self)?;
+                match self {
+                    Self::Length => Value::Number(items.len() as f64),
+                    Self::Reverse => Value::list(items.into_iter().rev().collect()),
+                    _ => items
+                        .last()
+                        .cloned()
+                        .ok_or_else(|| error(span, format!("{self} requires a non empty list")))?,
+                }
+            }
+            Self::Nth => {
+                arg_error(2, args.len() as u32, self, false, span)?;
+                let items = args[0].to_vec(span, self)?;
+                let index = args[1].clone().get_number(span, self)?;
+                if index < 0.0 || index.trunc() != index {
+                    return Err(error(
+                        span,
+                        format!("{self} requires a whole number index, given {index}"),
+                    ));
+                }
+                items.get(index as usize).cloned().ok_or_else(|| {
+                    error(
+                        span,
+                        format!(
+                            "index {index} is out of range for a list with {} items",
+                            items.len()
+                        ),
                     )
+                })?
+            }
+            Self::Append => {
+                arg_error(1, args.len() as u32, self, true, span)?;
+                let lists = args
+                    .iter()
+                    .map(|list| list.to_vec(span, self))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Value::list(lists.concat())
+            }
+            Self::Contains => {
+                arg_error(2, args.len() as u32, self, false, span)?;
+                Value::Boolean(args[0].to_vec(span, self)?.contains(&args[1]))
+            }
+            Self::Join => {
+                arg_error(2, args.len() as u32, self, false, span)?;
+                let separator = args[1].clone().get_string(span, self)?;
+                Value::String(
+                    args[0]
+                        .to_vec(span, self)?
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<String>>()
+                        .join(&separator),
+                )
+            }
+            Self::Split => {
+                arg_error(2, args.len() as u32, self, false, span)?;
+                let string = args[0].clone().get_string(span, self)?;
+                let separator = args[1].clone().get_string(span, self)?;
+                // an empty separator splits the string into its characters
+                let parts: Vec<Value> = if separator.is_empty() {
+                    string
+                        .chars()
+                        .map(|c| Value::String(c.to_string()))
+                        .collect()
+                } else {
+                    string
+                        .split(&separator)
+                        .map(|part| Value::String(part.to_string()))
+                        .collect()
+                };
+                Value::list(parts)
+            }
+            Self::Range => {
+                if !(2..=3).contains(&args.len()) {
+                    return Err(error(span, format!("{self} requires 2 or 3 arguments")));
                 }
-
-                keyword if crate::KEYWORDS.is_keyword(keyword) => {
-                    error::error(line, format!("Keyword not found {self}"));
+                let start = range_bound(&args[0], span, self)?;
+                let end = range_bound(&args[1], span, self)?;
+                let step = match args.get(2) {
+                    Some(step) => range_bound(step, span, self)?,
+                    None => 1.0,
+                };
+                if step == 0.0 {
+                    return Err(error(span, format!("{self} requires a step that is not 0")));
                 }
-                _ => {
-                    error::error(line, format!("Keyword not found {self}"));
+                // an infinite end never ends the range, but an infinite start or step goes nowhere
+                if !start.is_finite() || !step.is_finite() || end.is_nan() {
+                    return Err(error(
+                        span,
+                        format!(
+                            "{self} requires a finite start and step and an end that is not NaN, \
+                             found <range {start} {end} {step}>"
+                        ),
+                    ));
                 }
+                Value::Range(Range::new(start, end, step))
             }
-        } else {
-            error::error(line, format!("Unknown keyword, {self}"));
-        }
+            Self::Exit => {
+                if args.len() != 1 {
+                    return Err(error(
+                        span,
+                        format!("Expected 1 argument for {self:?} operator"),
+                    ));
+                }
+                match &args[0] {
+                    Value::Number(number) => return Err(UmplError::Exit(*number as i32)),
+                    _ => {
+                        return Err(error(
+                            span,
+                            format!("Expected number for {self:?} operator"),
+                        ))
+                    }
+                }
+            }
+            Self::SplitOn => {
+                if args.len() < 2 {
+                    return Err(error(
+                        span,
+                        format!("Expected al least 2 arguments for {self:?} operator"),
+                    ));
+                }
+                let Value::String(og_string) = &args[0] else {
+                    return Err(error(
+                        span,
+                        format!("Expected string for {self:?} operator"),
+                    ));
+                };
+                let Value::String(split_on) = &args[1] else {
+                    return Err(error(
+                        span,
+                        format!("Expected string for {self:?} operator"),
+                    ));
+                };
+                // check if there is a third argument (number)
+                args.get(2).map_or_else(
+                    || {
+                        Ok(og_string.split_once(split_on).map_or_else(
+                            || Value::String(og_string.clone()),
+                            |v| Value::String(v.0.to_string()),
+                        ))
+                    },
+                    |number| -> Result<Value, UmplError> {
+                        if let Value::Number(number) = number {
+                            let number = *number as usize;
+                            // return the string until the nth time split_on is found
+                            let string: Vec<&str> =
+                                og_string.split_inclusive(split_on).collect::<Vec<&str>>();
+                            if number > string.len() {
+                                return Err(error(
+                                    span,
+                                    format!("{number} is greater than the number of splits"),
+                                ));
+                            }
+                            // loop through the splits and add them to the string if they are less than the number
+                            let mut ret_string = String::new();
+                            string.iter().take(number).for_each(|i: &&str| {
+                                ret_string.push_str(i);
+                            });
+                            let ret_string = ret_string
+                                .rsplit_once(split_on)
+                                .map_or_else(|| og_string.clone(), |string| string.0.to_string());
+                            Ok(Value::String(ret_string))
+                        } else {
+                            Err(error(
+                                span,
+                                format!("Expected number for {self:?} operator"),
+                            ))
+                        }
+                    },
+                )?
+            }
+
+            _ => {
+                return Err(error(span, format!("Keyword not found {self}")));
+            }
+        })
+    }
+}
+
+/// a start, end or step of a range, numbers are written in hex so a decimal one is given as a string like `1.5`
+fn range_bound(value: &Value, span: &Span, keyword: &TokenType) -> Result<f64, UmplError> {
+    match value {
+        Value::Number(number) => Ok(*number),
+        Value::String(decimal) => decimal.trim().parse().map_err(|_| {
+            error(
+                span,
+                format!("{keyword} requires a number or a string with a decimal number, found `{decimal}`"),
+            )
+        }),
+        value => Err(error(
+            span,
+            format!(
+                "{keyword} requires a number or a string with a decimal number, found a {}",
+                value.get_type()
+            ),
+        )),
     }
 }
 
 impl Display for TokenType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "TokenType {self:?}",)
+        write!(f, "TokenType {self:?}")
+    }
+}
+
+/// A region of source code.
+/// Lines and columns are 1-based (counted in characters) and byte offsets index into the source,
+/// the end of a span is exclusive.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Span {
+    pub filename: String,
+    pub start_line: i32,
+    pub start_column: i32,
+    pub end_line: i32,
+    pub end_column: i32,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+impl Span {
+    pub fn new(
+        filename: &str,
+        (start_line, start_column, start_byte): (i32, i32, usize),
+        (end_line, end_column, end_byte): (i32, i32, usize),
+    ) -> Self {
+        Self {
+            filename: filename.to_string(),
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            start_byte,
+            end_byte,
+        }
+    }
+
+    /// creates a span that starts where this one starts and ends where `end` ends
+    #[must_use]
+    pub fn to(&self, end: &Self) -> Self {
+        Self {
+            filename: self.filename.clone(),
+            start_line: self.start_line,
+            start_column: self.start_column,
+            end_line: end.end_line,
+            end_column: end.end_column,
+            start_byte: self.start_byte,
+            end_byte: end.end_byte,
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.filename, self.start_line, self.start_column
+        )
     }
 }
 
@@ -432,17 +684,15 @@ impl Display for TokenType {
 pub struct Token {
     pub token_type: TokenType,
     pub lexeme: String,
-    pub filename: String,
-    pub line: i32,
+    pub span: Span,
 }
 
 impl Token {
-    pub fn new(token_type: TokenType, lexeme: &str, line: i32, filename: &str) -> Self {
+    pub fn new(token_type: TokenType, lexeme: &str, span: Span) -> Self {
         Self {
             token_type,
             lexeme: lexeme.to_string(),
-            line,
-            filename: filename.to_string(),
+            span,
         }
     }
 }
@@ -463,6 +713,6 @@ impl Display for Token {
 
 impl fmt::Debug for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{self} at {}", self.line)
+        write!(f, "{self} at {}", self.span)
     }
 }
@@ -0,0 +1,450 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    error::{error, UmplError},
+    eval::Scope,
+    parser::rules::{Ast, LiteralNode},
+    token::{Span, TokenType},
+};
+
+/// a value that the evaluator works with, anything that can be stored in a variable, passed to a function or returned from one
+#[derive(Clone, Debug)]
+pub enum Value {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Hempty,
+    /// a path to a file
+    File(String),
+    /// a pair made with list, shared so changing a car or cdr is seen by every variable holding the list
+    Cons(Rc<RefCell<Cons>>),
+    Function(Rc<Potato>),
+    Range(Range),
+}
+
+#[derive(Clone, Debug)]
+pub struct Cons {
+    pub car: Value,
+    pub cdr: Value,
+}
+
+/// numbers from start up to but not including end, made one at a time when looped over
+#[derive(PartialEq, Clone, Debug)]
+pub struct Range {
+    pub start: f64,
+    pub end: f64,
+    /// never 0, a negative step counts down
+    pub step: f64,
+    /// how many numbers were already made
+    taken: u64,
+}
+
+impl Range {
+    pub const fn new(start: f64, end: f64, step: f64) -> Self {
+        Self {
+            start,
+            end,
+            step,
+            taken: 0,
+        }
+    }
+}
+
+impl Iterator for Range {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        // each number is worked out from the start so a step like 0.1 does not add up rounding errors
+        let number = self.step.mul_add(self.taken as f64, self.start);
+        let done = if self.step > 0.0 {
+            number >= self.end
+        } else {
+            number <= self.end
+        };
+        if done {
+            return None;
+        }
+        self.taken += 1;
+        Some(number)
+    }
+}
+
+/// a function declared with potato, or made by a potato without a name
+#[derive(Clone)]
+pub struct Potato {
+    /// lambdas have no name
+    pub name: Option<char>,
+    pub body: Vec<Ast>,
+    pub num_arguments: f64,
+    /// arguments past `num_arguments` are put in a list
+    pub extra_arguments: bool,
+    /// given before the arguments of every call, like the name a missing function handler is called for
+    pub bound_arguments: Vec<Value>,
+    /// the scope the function was declared in, it is called in a scope inside of it instead of the caller's.
+    /// the scope holds the functions declared in it, so this does not keep it alive
+    pub scope: Weak<RefCell<Scope>>,
+    /// keeps the scope alive while the function is a value, which can outlive the scope it was declared in
+    keep_alive: Option<Rc<RefCell<Scope>>>,
+}
+
+impl Potato {
+    pub fn new(
+        name: Option<char>,
+        body: Vec<Ast>,
+        num_arguments: f64,
+        extra_arguments: bool,
+        scope: &Rc<RefCell<Scope>>,
+    ) -> Self {
+        Self {
+            name,
+            body,
+            num_arguments,
+            extra_arguments,
+            bound_arguments: Vec::new(),
+            scope: Rc::downgrade(scope),
+            keep_alive: None,
+        }
+    }
+
+    /// the function as a value, it keeps the scope the function was declared in alive
+    pub fn to_value(self: &Rc<Self>) -> Value {
+        if self.keep_alive.is_some() {
+            return Value::Function(Rc::clone(self));
+        }
+        let mut function = (**self).clone();
+        function.keep_alive = function.scope.upgrade();
+        Value::Function(Rc::new(function))
+    }
+
+    /// the scope the function was declared in, which is alive while the function can be called
+    pub fn scope(&self) -> Rc<RefCell<Scope>> {
+        self.scope
+            .upgrade()
+            .expect("scope of a function was dropped")
+    }
+}
+
+impl PartialEq for Potato {
+    fn eq(&self, other: &Self) -> bool {
+        // two functions with the same body are only the same function if they were declared in the same scope
+        self.scope.ptr_eq(&other.scope)
+            && self.name == other.name
+            && self.body == other.body
+            && self.num_arguments == other.num_arguments
+            && self.extra_arguments == other.extra_arguments
+            && self.bound_arguments == other.bound_arguments
+    }
+}
+
+impl fmt::Debug for Potato {
+    // the scope is left out, it can hold the function itself
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Potato")
+            .field("name", &self.name)
+            .field("body", &self.body)
+            .field("num_arguments", &self.num_arguments)
+            .field("extra_arguments", &self.extra_arguments)
+            .field("bound_arguments", &self.bound_arguments)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Display for Potato {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "potato {name}"),
+            None => write!(f, "potato"),
+        }
+    }
+}
+
+impl Value {
+    pub fn cons(car: Self, cdr: Self) -> Self {
+        Self::Cons(Rc::new(RefCell::new(Cons { car, cdr })))
+    }
+
+    /// makes a list of `items` out of pairs, ending with hempty
+    pub fn list(items: Vec<Self>) -> Self {
+        Self::dotted_list(items, Self::Hempty)
+    }
+
+    /// makes a list of `items` out of pairs, the last pair's cdr is `tail`
+    pub fn dotted_list(items: Vec<Self>, tail: Self) -> Self {
+        items
+            .into_iter()
+            .rev()
+            .fold(tail, |cdr, car| Self::cons(car, cdr))
+    }
+
+    /// a copy that shares no pairs with the value, so changing one list does not change the other
+    #[must_use]
+    pub fn deep_copy(&self) -> Self {
+        self.copy_pairs(&mut HashMap::new())
+    }
+
+    /// the pairs already copied are reused, so a list that points back into itself still does
+    fn copy_pairs(&self, copies: &mut HashMap<*const RefCell<Cons>, Rc<RefCell<Cons>>>) -> Self {
+        let Self::Cons(first) = self else {
+            return self.clone();
+        };
+        if let Some(copy) = copies.get(&Rc::as_ptr(first)) {
+            return Self::Cons(Rc::clone(copy));
+        }
+        let new_pair = || {
+            Rc::new(RefCell::new(Cons {
+                car: Self::Hempty,
+                cdr: Self::Hempty,
+            }))
+        };
+        let head = new_pair();
+        copies.insert(Rc::as_ptr(first), Rc::clone(&head));
+        let (mut original, mut copy) = (Rc::clone(first), Rc::clone(&head));
+        // the cdrs are followed in a loop so a long list does not need a deep stack
+        loop {
+            let (car, cdr) = {
+                let original = original.borrow();
+                (original.car.clone(), original.cdr.clone())
+            };
+            copy.borrow_mut().car = car.copy_pairs(copies);
+            match cdr {
+                Self::Cons(next) if !copies.contains_key(&Rc::as_ptr(&next)) => {
+                    let next_copy = new_pair();
+                    copies.insert(Rc::as_ptr(&next), Rc::clone(&next_copy));
+                    copy.borrow_mut().cdr = Self::Cons(Rc::clone(&next_copy));
+                    (original, copy) = (next, next_copy);
+                }
+                cdr => {
+                    copy.borrow_mut().cdr = cdr.copy_pairs(copies);
+                    return Self::Cons(head);
+                }
+            }
+        }
+    }
+
+    /// pairs that are already being compared are taken to be equal,
+    /// so two lists that point back into themselves the same way are equal instead of being compared forever
+    fn equals(
+        &self,
+        other: &Self,
+        comparing: &mut HashSet<(*const RefCell<Cons>, *const RefCell<Cons>)>,
+    ) -> bool {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a == b,
+            (Self::String(a), Self::String(b)) | (Self::File(a), Self::File(b)) => a == b,
+            (Self::Boolean(a), Self::Boolean(b)) => a == b,
+            (Self::Hempty, Self::Hempty) => true,
+            (Self::Function(a), Self::Function(b)) => a == b,
+            (Self::Range(a), Self::Range(b)) => a == b,
+            (Self::Cons(a), Self::Cons(b)) => {
+                let (mut a, mut b) = (Rc::clone(a), Rc::clone(b));
+                // the cdrs are followed in a loop so a long list does not need a deep stack
+                loop {
+                    if Rc::ptr_eq(&a, &b) || !comparing.insert((Rc::as_ptr(&a), Rc::as_ptr(&b))) {
+                        return true;
+                    }
+                    let (a_pair, b_pair) = (a.borrow().clone(), b.borrow().clone());
+                    if !a_pair.car.equals(&b_pair.car, comparing) {
+                        return false;
+                    }
+                    match (a_pair.cdr, b_pair.cdr) {
+                        (Self::Cons(next_a), Self::Cons(next_b)) => (a, b) = (next_a, next_b),
+                        (a_cdr, b_cdr) => return a_cdr.equals(&b_cdr, comparing),
+                    }
+                }
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get_file(self, span: &Span, keyword: &TokenType) -> Result<String, UmplError> {
+        match self {
+            Self::File(file) => Ok(file),
+            _ => Err(error(span, format!("{keyword} requires a file"))),
+        }
+    }
+
+    pub fn get_string(self, span: &Span, keyword: &TokenType) -> Result<String, UmplError> {
+        match self {
+            Self::String(string) => Ok(string),
+            _ => Err(error(span, format!("{keyword} requires a string"))),
+        }
+    }
+
+    pub fn get_number(self, span: &Span, keyword: &TokenType) -> Result<f64, UmplError> {
+        match self {
+            Self::Number(number) => Ok(number),
+            _ => Err(error(span, format!("{keyword} requires a number"))),
+        }
+    }
+
+    pub fn get_bool(self, span: &Span, keyword: &TokenType) -> Result<bool, UmplError> {
+        match self {
+            Self::Boolean(boolean) => Ok(boolean),
+            _ => Err(error(span, format!("{keyword} requires a boolean"))),
+        }
+    }
+
+    pub fn get_cons(
+        self,
+        span: &Span,
+        keyword: &TokenType,
+    ) -> Result<Rc<RefCell<Cons>>, UmplError> {
+        match self {
+            Self::Cons(cons) => Ok(cons),
+            _ => Err(error(span, format!("{keyword} requires a list"))),
+        }
+    }
+
+    pub fn get_function(self, span: &Span, keyword: &TokenType) -> Result<Rc<Potato>, UmplError> {
+        match self {
+            Self::Function(function) => Ok(function),
+            _ => Err(error(span, format!("{keyword} requires a function"))),
+        }
+    }
+
+    /// the items of a list ending with hempty, hempty by itself is the empty list and a range is made into a list
+    pub fn to_vec(&self, span: &Span, keyword: &TokenType) -> Result<Vec<Self>, UmplError> {
+        if let Self::Range(range) = self {
+            if range.end.is_infinite() && (range.end > 0.0) == (range.step > 0.0) {
+                return Err(error(
+                    span,
+                    format!("{keyword} cannot turn a range that never ends into a list"),
+                ));
+            }
+            return Ok(range.clone().map(Self::Number).collect());
+        }
+        let mut items = Vec::new();
+        let mut seen = HashSet::new();
+        let mut rest = self.clone();
+        loop {
+            match rest {
+                Self::Hempty => return Ok(items),
+                Self::Cons(cons) => {
+                    if !seen.insert(Rc::as_ptr(&cons)) {
+                        return Err(error(
+                            span,
+                            format!("{keyword} requires a list ending with hempty, found a list that points back into itself"),
+                        ));
+                    }
+                    let cons = cons.borrow();
+                    items.push(cons.car.clone());
+                    let cdr = cons.cdr.clone();
+                    drop(cons);
+                    rest = cdr;
+                }
+                _ => {
+                    return Err(error(
+                        span,
+                        format!("{keyword} requires a list ending with hempty"),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// every name `get_type` can give back
+    pub const TYPES: [&'static str; 8] = [
+        "number", "string", "boolean", "hempty", "file", "list", "function", "range",
+    ];
+
+    pub fn get_type(&self) -> String {
+        match self {
+            Self::Number(_) => "number".to_string(),
+            Self::String(_) => "string".to_string(),
+            Self::Boolean(_) => "boolean".to_string(),
+            Self::Hempty => "hempty".to_string(),
+            Self::File(_) => "file".to_string(),
+            Self::Cons(_) => "list".to_string(),
+            Self::Function(_) => "function".to_string(),
+            Self::Range(_) => "range".to_string(),
+        }
+    }
+
+    pub fn type_eq(&self, other: &Self) -> bool {
+        self.get_type() == other.get_type()
+    }
+}
+
+impl From<LiteralNode> for Value {
+    fn from(literal: LiteralNode) -> Self {
+        match literal {
+            LiteralNode::Number(number) => Self::Number(number),
+            LiteralNode::String(string) => Self::String(string),
+            LiteralNode::Boolean(boolean) => Self::Boolean(boolean),
+            LiteralNode::Hempty => Self::Hempty,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::String(string) => write!(f, "{string}"),
+            Self::Boolean(boolean) => write!(f, "{boolean}"),
+            Self::Hempty => write!(f, "hempty"),
+            Self::File(file) => write!(f, "<file {file}>"),
+            Self::Function(function) => write!(f, "<{function}>"),
+            Self::Range(range) => write!(f, "<range {} {} {}>", range.start, range.end, range.step),
+            Self::Cons(cons) => write_list(cons, f, &mut Vec::new()),
+        }
+    }
+}
+
+/// writes the list the way it would be written, [1 2 3] for a list and [1 2 . 3] if it does not end with hempty,
+/// a pair that is already being written is in `writing` and is written as ... so a list that points back into itself ends
+fn write_list(
+    first: &Rc<RefCell<Cons>>,
+    f: &mut fmt::Formatter<'_>,
+    writing: &mut Vec<*const RefCell<Cons>>,
+) -> fmt::Result {
+    let outer = writing.len();
+    let mut cons = Rc::clone(first);
+    write!(f, "[")?;
+    let result = loop {
+        writing.push(Rc::as_ptr(&cons));
+        let (car, cdr) = {
+            let pair = cons.borrow();
+            (pair.car.clone(), pair.cdr.clone())
+        };
+        write_item(&car, f, writing)?;
+        match cdr {
+            Value::Hempty => break write!(f, "]"),
+            Value::Cons(next) if writing.contains(&Rc::as_ptr(&next)) => break write!(f, " ...]"),
+            Value::Cons(next) => {
+                write!(f, " ")?;
+                cons = next;
+            }
+            tail => {
+                write!(f, " . ")?;
+                write_item(&tail, f, writing)?;
+                break write!(f, "]");
+            }
+        }
+    };
+    writing.truncate(outer);
+    result
+}
+
+fn write_item(
+    item: &Value,
+    f: &mut fmt::Formatter<'_>,
+    writing: &mut Vec<*const RefCell<Cons>>,
+) -> fmt::Result {
+    match item {
+        Value::Cons(cons) if writing.contains(&Rc::as_ptr(cons)) => write!(f, "..."),
+        Value::Cons(cons) => write_list(cons, f, writing),
+        item => write!(f, "{item}"),
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other, &mut HashSet::new())
+    }
+}
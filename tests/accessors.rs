@@ -0,0 +1,49 @@
+//! `.car` and `.cdr` can be chained to read or change any part of a list, cadr, cddr and caddr work on any list value
+mod common;
+
+use common::error_message;
+use umpl::{run_source, value::Value};
+
+const LIST: &str = "list x with [1 2 3 4]\n";
+
+fn value(src: &str) -> Value {
+    run_source(&format!("{LIST}{src}"), "accessors.umpl").unwrap()
+}
+
+#[test]
+fn read_a_chain() {
+    assert_eq!(value("(x.cdr.cdr.car)<"), Value::Number(3.0));
+    assert_eq!(value("(x.cdr.cdr.cdr.cdr)<"), Value::Hempty);
+}
+
+#[test]
+fn setwith_a_chain() {
+    assert_eq!(
+        value("((setwith x.cdr.cdr.car 9))<\n(x)<").to_string(),
+        "[1 2 9 4]"
+    );
+    assert_eq!(
+        value("((setwith x.cdr.cdr [7]))<\n(x)<").to_string(),
+        "[1 2 7]"
+    );
+}
+
+#[test]
+fn combined_accessors() {
+    assert_eq!(value("((cadr x))<"), Value::Number(2.0));
+    assert_eq!(value("((cddr x))<").to_string(), "[3 4]");
+    assert_eq!(value("((caddr x))<"), Value::Number(3.0));
+    assert_eq!(value("((caddr [5 6 7]))<"), Value::Number(7.0));
+}
+
+#[test]
+fn chain_past_the_end() {
+    assert_eq!(
+        error_message(&format!("{LIST}(x.cdr.cdr.cdr.cdr.car)<"), "accessors.umpl"),
+        "expected list, got something else"
+    );
+    assert_eq!(
+        error_message("((cadr [1]))<", "accessors.umpl"),
+        "TokenType Cadr requires a list"
+    );
+}
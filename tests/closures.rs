@@ -0,0 +1,55 @@
+//! a potato used as a value keeps the scope it was made in alive, and lets go of it once the program is done
+use umpl::{run_source, value::Value};
+
+/// runs the code, which ends with a potato, and checks that the potato still works and that its scope is freed with it
+fn assert_scope_freed(src: &str) {
+    let Value::Function(function) = run_source(src, "closures.umpl").unwrap() else {
+        panic!("expected a potato");
+    };
+    let scope = function.scope.clone();
+    assert!(scope.upgrade().is_some());
+    drop(function);
+    assert!(scope.upgrade().is_none());
+}
+
+#[test]
+fn lambda_in_the_scope_it_was_made_in() {
+    assert_scope_freed(
+        "
+potato 🏦 0 ⧼
+    create f with potato 0 ⧼ return 1 ⧽
+    return f
+⧽
+((new 🏦))<",
+    );
+}
+
+#[test]
+fn named_potato_in_the_scope_it_was_declared_in() {
+    assert_scope_freed(
+        "
+potato 🏦 0 ⧼
+    potato 💸 0 ⧼ return 1 ⧽
+    create f with 💸
+    return f
+⧽
+((new 🏦))<",
+    );
+}
+
+#[test]
+fn lambda_kept_in_a_parent_scope() {
+    assert_scope_freed(
+        "
+potato 🏦 1 ⧼
+    create balance with $1
+    return potato 1 ⧼
+        ((subtractwith balance $1))<
+        return balance
+    ⧽
+⧽
+create spend with ((new 🏦 0xA))<
+((new spend 1))<
+(spend)<",
+    );
+}
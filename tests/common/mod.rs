@@ -0,0 +1,22 @@
+//! helpers shared by the integration tests, not every test file uses all of them
+#![allow(dead_code)]
+use std::{env, fs, path::PathBuf, process};
+use umpl::{error::UmplError, run_source};
+
+/// a directory only `test` writes to, so tests running at the same time never share files
+pub fn test_dir(test: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("umpl_{test}_{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// runs the code and gives back the message of the error it ends with, or of its only syntax error
+pub fn error_message(src: &str, name: &str) -> String {
+    match run_source(src, name) {
+        Err(UmplError::Diagnostic(diagnostic)) => diagnostic.message,
+        Err(UmplError::Diagnostics(mut diagnostics)) if diagnostics.len() == 1 => {
+            diagnostics.remove(0).message
+        }
+        other => panic!("expected an error, got {other:?}"),
+    }
+}
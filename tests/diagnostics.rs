@@ -0,0 +1,39 @@
+//! a diagnostic shows the line of code it points at, from the file it points into
+mod common;
+
+use common::test_dir;
+use std::fs;
+use umpl::run_source;
+
+fn rendered(src: &str, name: &str) -> String {
+    run_source(src, name).unwrap_err().to_string()
+}
+
+#[test]
+fn runs_with_the_same_name() {
+    let first = run_source("create x with 1\n((error `first`))<", "same.umpl").unwrap_err();
+    let second = rendered("((error `second`))<", "same.umpl");
+    assert!(second.contains("1 | ((error `second`))<"), "{second}");
+    // the second run does not change what the first one's error shows
+    let first = first.to_string();
+    assert!(first.contains("2 | ((error `first`))<"), "{first}");
+}
+
+#[test]
+fn error_in_a_module() {
+    let dir = test_dir("error_in_a_module");
+    fs::write(
+        dir.join("failing.umpl"),
+        "potato 😀 0 ⧼\n    return ((error `boom`))<\n⧽\n",
+    )
+    .unwrap();
+    let main = dir.join("main.umpl").display().to_string();
+    let error = rendered(
+        "((module `failing` `failing.umpl`))<\n((new failing$😀))<",
+        &main,
+    );
+    assert!(
+        error.contains("2 |     return ((error `boom`))<"),
+        "{error}"
+    );
+}
@@ -0,0 +1,54 @@
+//! a potato's emoji used as a value is found the same way as when it is called
+mod common;
+
+use common::{error_message, test_dir};
+use std::fs;
+use umpl::{run_source, value::Value};
+
+fn module_dir(test: &str) -> String {
+    let dir = test_dir(test);
+    fs::write(
+        dir.join("handler.umpl"),
+        "potato 🤷 1* ⧼
+    return ((plus $1 `:` (length $2)))<
+⧽",
+    )
+    .unwrap();
+    dir.join("main.umpl").display().to_string()
+}
+
+#[test]
+fn missing_function_handler_for_a_call() {
+    let src = "((module `m` `handler.umpl`))<
+((new m$🎈 1))<";
+    assert_eq!(
+        run_source(src, &module_dir("handler_for_a_call")).unwrap(),
+        Value::String("🎈:1".to_string())
+    );
+}
+
+#[test]
+fn missing_function_handler_for_map() {
+    let src = "((module `m` `handler.umpl`))<
+((join (map m$🎈 [1 2]) ` `))<";
+    assert_eq!(
+        run_source(src, &module_dir("handler_for_map")).unwrap(),
+        Value::String("🎈:1 🎈:1".to_string())
+    );
+}
+
+#[test]
+fn module_not_loaded() {
+    assert_eq!(
+        error_message("((map q$🎈 [1 2]))<", "main.umpl"),
+        "module q is not loaded"
+    );
+}
+
+#[test]
+fn function_not_defined() {
+    assert_eq!(
+        error_message("((map 🎈 [1 2]))<", "main.umpl"),
+        "Function 🎈 is not defined"
+    );
+}
@@ -0,0 +1,61 @@
+//! break label and continue label go to the loop with that label instead of the innermost one
+mod common;
+
+use common::error_message;
+use umpl::{run_source, value::Value};
+
+fn value(src: &str) -> Value {
+    run_source(src, "loops.umpl").unwrap()
+}
+
+#[test]
+fn break_the_outer_loop() {
+    let src = "create n with 0
+loop outer ⧼
+    loop ⧼
+        ((setwith n (plus n 1)))<
+        if {((eq n 3))<} ⧼ break outer ⧽
+    ⧽
+⧽
+(n)<";
+    assert_eq!(value(src), Value::Number(3.0));
+}
+
+#[test]
+fn continue_the_outer_loop() {
+    let src = "list seen with []
+loop outer over i with [1 2 3] ⧼
+    loop over j with [1 2 3] ⧼
+        if {((eq j 2))<} ⧼ continue outer ⧽
+        ((setwith seen (append seen [(plus i j)])))<
+    ⧽
+⧽
+(seen)<";
+    assert_eq!(value(src).to_string(), "[2 3 4]");
+}
+
+#[test]
+fn break_the_outer_loop_with_a_value() {
+    let src = "create found with loop rows over i with [1 2] ⧼
+    loop over j with [4 5] ⧼
+        if {((eq j 5))<} ⧼ break rows with ((plus i j))< ⧽
+    ⧽
+⧽
+(found)<";
+    assert_eq!(value(src), Value::Number(6.0));
+}
+
+#[test]
+fn unknown_label() {
+    assert_eq!(
+        error_message("loop ⧼ break nowhere ⧽", "loops.umpl"),
+        "no loop labelled nowhere around this break"
+    );
+    assert_eq!(
+        error_message(
+            "loop outer ⧼ potato 😀 0 ⧼ loop ⧼ continue outer ⧽ ⧽ ⧽",
+            "loops.umpl"
+        ),
+        "no loop labelled outer around this continue"
+    );
+}
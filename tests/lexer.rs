@@ -0,0 +1,58 @@
+//! the lexer turns strings, escapes and hex numbers into values, and points at the character it cannot lex
+mod common;
+
+use common::error_message;
+use umpl::{error::UmplError, run_source, value::Value};
+
+fn value(src: &str) -> Value {
+    run_source(src, "lexer.umpl").unwrap()
+}
+
+#[test]
+fn string_escapes() {
+    assert_eq!(
+        value("(`a\\tb\\\\c\\`d\\x41\\u1F600`)<"),
+        Value::String("a\tb\\c`dA😀".to_string())
+    );
+}
+
+#[test]
+fn numbers_are_hex() {
+    assert_eq!(value("(10)<"), Value::Number(16.0));
+    assert_eq!(value("(0xA)<"), Value::Number(10.0));
+    assert_eq!(value("(1.8)<"), Value::Number(1.5));
+}
+
+#[test]
+fn long_string() {
+    let text = "a".repeat(100_000);
+    assert_eq!(value(&format!("(`{text}`)<")), Value::String(text));
+}
+
+#[test]
+fn unknown_character() {
+    match run_source("(`😀`)<\n  🧮", "lexer.umpl") {
+        Err(UmplError::Diagnostic(diagnostic)) => {
+            assert_eq!(diagnostic.message, "uknown character 🧮");
+            let span = &diagnostic.labels[0].span;
+            assert_eq!((span.start_line, span.start_column), (2, 3));
+        }
+        other => panic!("expected an error, got {other:?}"),
+    }
+}
+
+#[test]
+fn unknown_escape() {
+    assert_eq!(
+        error_message("(`\\q`)<", "lexer.umpl"),
+        "unknown escape sequence q"
+    );
+}
+
+#[test]
+fn unterminated_string() {
+    assert_eq!(
+        error_message("(`abc)<", "lexer.umpl"),
+        "unterminated string"
+    );
+}
@@ -0,0 +1,127 @@
+//! potatoes see the variables of the scope they are declared in, not the ones of the scope they are called from
+mod common;
+
+use common::test_dir;
+use std::fs;
+use umpl::{run_source, value::Value};
+
+/// runs the code and gives back what the expression is at the end of it
+fn value_after(src: &str, expression: &str, name: &str) -> Value {
+    run_source(&format!("{src}\n({expression})<\n"), name).unwrap()
+}
+
+#[test]
+fn called_from_another_potato() {
+    let src = "
+create x with `global`
+potato 🔍 0 ⧼
+    return x
+⧽
+potato 🎭 0 ⧼
+    create x with `caller`
+    return ((new 🔍))<
+⧽";
+    assert_eq!(
+        value_after(src, "(new 🎭)", "scope.umpl"),
+        Value::String("global".to_string())
+    );
+}
+
+#[test]
+fn nested_closures() {
+    let src = "
+potato 🏦 1 ⧼
+    create balance with $1
+    potato 💸 1 ⧼
+        ((subtractwith balance $1))<
+        return balance
+    ⧽
+    return 💸
+⧽
+create spend with ((new 🏦 0x64))<
+create save with ((new 🏦 0xA))<
+create first with ((new spend 0x14))<
+create other with ((new save 1))<
+create second with ((new spend 0x14))<";
+    // 0x64 - 0x14 is 80 and then 60, the other closure has a balance of its own
+    assert_eq!(value_after(src, "first", "scope.umpl"), Value::Number(80.0));
+    assert_eq!(value_after(src, "other", "scope.umpl"), Value::Number(9.0));
+    assert_eq!(
+        value_after(src, "second", "scope.umpl"),
+        Value::Number(60.0)
+    );
+}
+
+#[test]
+fn potato_in_a_loop() {
+    let src = "
+create i with 0
+loop ⧼
+    potato ➕ 0 ⧼
+        return ((plus i 1))<
+    ⧽
+    ((setwith i (new ➕)))<
+    if {((eq i 3))<} ⧼ break ⧽
+⧽";
+    assert_eq!(value_after(src, "i", "scope.umpl"), Value::Number(3.0));
+}
+
+#[test]
+fn module_potato() {
+    let dir = test_dir("module_potato");
+    fs::write(
+        dir.join("greeter.umpl"),
+        "create greeting with `from the module`
+potato 👋 0 ⧼
+    return greeting
+⧽
+potato 🗣 1 ⧼
+    return ((plus greeting ` and ` $1))<
+⧽",
+    )
+    .unwrap();
+    let main = dir.join("main.umpl").display().to_string();
+    let src = "
+create greeting with `from the main file`
+((module `greeter` `greeter.umpl`))<";
+    assert_eq!(
+        value_after(src, "(new greeter$👋)", &main),
+        Value::String("from the module".to_string())
+    );
+    // the caller's variable only gets in as an argument
+    assert_eq!(
+        value_after(src, "(new greeter$🗣 greeting)", &main),
+        Value::String("from the module and from the main file".to_string())
+    );
+}
+
+#[test]
+fn potato_sees_the_iteration() {
+    let src = "
+create total with 0
+loop over i with [1 2 3] ⧼
+    create y with ((multiply i 0xA))<
+    potato 🔢 0 ⧼
+        return ((plus y 1))<
+    ⧽
+    ((addwith total (new 🔢)))<
+⧽";
+    // 10 + 1, 20 + 1 and 30 + 1
+    assert_eq!(value_after(src, "total", "scope.umpl"), Value::Number(63.0));
+}
+
+#[test]
+fn potato_sees_the_loop_over_variable() {
+    let src = "
+create seen with ``
+loop over i with ((range 0 3))< ⧼
+    potato 👀 0 ⧼
+        return i
+    ⧽
+    ((setwith seen (plus seen (new 👀))))<
+⧽";
+    assert_eq!(
+        value_after(src, "seen", "scope.umpl"),
+        Value::String("012".to_string())
+    );
+}
@@ -0,0 +1,61 @@
+//! the list keywords work on lists of pairs ending with hempty
+mod common;
+
+use common::error_message;
+use umpl::{run_source, value::Value};
+
+fn value(src: &str) -> Value {
+    run_source(src, "lists.umpl").unwrap()
+}
+
+#[test]
+fn length_nth_and_last() {
+    assert_eq!(value("((length [1 2 3]))<"), Value::Number(3.0));
+    assert_eq!(value("((length []))<"), Value::Number(0.0));
+    assert_eq!(value("((nth [4 5 6] 2))<"), Value::Number(6.0));
+    assert_eq!(value("((last [4 5 6]))<"), Value::Number(6.0));
+}
+
+#[test]
+fn append_and_reverse() {
+    assert_eq!(value("((append [1 2] [3] []))<").to_string(), "[1 2 3]");
+    assert_eq!(value("((reverse [1 2 3]))<").to_string(), "[3 2 1]");
+    // the lists given are left alone
+    assert_eq!(
+        value("list x with [1 2]\n((append x [3]))<\n((reverse x))<\n(x)<").to_string(),
+        "[1 2]"
+    );
+}
+
+#[test]
+fn contains() {
+    assert_eq!(value("((contains [1 2 3] 2))<"), Value::Boolean(true));
+    assert_eq!(value("((contains [1 2 3] 7))<"), Value::Boolean(false));
+    assert_eq!(value("((contains [[1] 2] [1]))<"), Value::Boolean(true));
+}
+
+#[test]
+fn join_and_split() {
+    assert_eq!(
+        value("((join [1 2 3] `, `))<"),
+        Value::String("1, 2, 3".to_string())
+    );
+    assert_eq!(value("((split `a,b,c` `,`))<").to_string(), "[a b c]");
+    assert_eq!(value("((split `abc` ``))<").to_string(), "[a b c]");
+    assert_eq!(
+        value("((join (split `a b` ` `) `-`))<"),
+        Value::String("a-b".to_string())
+    );
+}
+
+#[test]
+fn wrong_values() {
+    assert_eq!(
+        error_message("((nth [1 2] 5))<", "lists.umpl"),
+        "index 5 is out of range for a list with 2 items"
+    );
+    assert_eq!(
+        error_message("((length [1 . 2]))<", "lists.umpl"),
+        "TokenType Length requires a list ending with hempty"
+    );
+}
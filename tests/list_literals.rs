@@ -0,0 +1,45 @@
+//! `[a b c]` builds a list of pairs ending with hempty, `[a . b]` is a single pair
+use umpl::{run_source, value::Value};
+
+fn value(src: &str) -> Value {
+    run_source(src, "lists.umpl").unwrap()
+}
+
+#[test]
+fn any_number_of_items() {
+    let list = value("list x with [1 2 3 4 5]\n(x)<");
+    assert_eq!(list.to_string(), "[1 2 3 4 5]");
+    assert_eq!(
+        list,
+        Value::list(vec![
+            Value::Number(1.0),
+            Value::Number(2.0),
+            Value::Number(3.0),
+            Value::Number(4.0),
+            Value::Number(5.0),
+        ])
+    );
+}
+
+#[test]
+fn empty_list_is_hempty() {
+    assert_eq!(value("([])<"), Value::Hempty);
+}
+
+#[test]
+fn dotted_pairs() {
+    assert_eq!(value("([1 . 2])<").to_string(), "[1 . 2]");
+    assert_eq!(value("((cdr [1 . 2]))<"), Value::Number(2.0));
+    assert_eq!(value("([1 2 . 3])<").to_string(), "[1 2 . 3]");
+    assert_eq!(value("((cdr [1 2]))<").to_string(), "[2]");
+}
+
+#[test]
+fn literal_used_as_a_value() {
+    assert_eq!(value("([[1 2] 3])<").to_string(), "[[1 2] 3]");
+    assert_eq!(
+        value("create names with [`a` `b`]\n((eq names [`a` `b`]))<"),
+        Value::Boolean(true)
+    );
+    assert_eq!(value("((car [(plus 1 2) 5]))<"), Value::Number(3.0));
+}
@@ -0,0 +1,34 @@
+//! modules are looked for next to the importing file, then in the search path, then in the current directory
+mod common;
+
+use common::test_dir;
+use std::fs;
+use umpl::{run_source, value::Value};
+
+// tests are run from the crate's directory, so the examples can be opened from the current directory
+const SRC: &str = "((module `m` `umpl_examples/lexical_scope_module.umpl`))<
+((new m$👋))<";
+
+#[test]
+fn current_directory() {
+    let dir = test_dir("current_directory");
+    assert_eq!(
+        run_source(SRC, &dir.join("main.umpl").display().to_string()).unwrap(),
+        Value::String("hello from the module".to_string())
+    );
+}
+
+#[test]
+fn next_to_the_importer_before_the_current_directory() {
+    let dir = test_dir("before_the_current_directory");
+    fs::create_dir_all(dir.join("umpl_examples")).unwrap();
+    fs::write(
+        dir.join("umpl_examples/lexical_scope_module.umpl"),
+        "potato 👋 0 ⧼ return `hello from next to main` ⧽\n",
+    )
+    .unwrap();
+    assert_eq!(
+        run_source(SRC, &dir.join("main.umpl").display().to_string()).unwrap(),
+        Value::String("hello from next to main".to_string())
+    );
+}
@@ -0,0 +1,20 @@
+//! variables from a module are read-only, even a list that is changed through another variable
+mod common;
+
+use common::test_dir;
+use std::fs;
+use umpl::{run_source, value::Value};
+
+#[test]
+fn changing_a_list_from_a_module() {
+    let dir = test_dir("changing_a_list_from_a_module");
+    fs::write(dir.join("m.umpl"), "list lst with [1 2]\n").unwrap();
+    let src = "((module `m` `m.umpl`))<
+create l with m$lst
+((setwith l.car 0x99))<
+((plus `` (car l) ` ` (car m$lst)))<";
+    assert_eq!(
+        run_source(src, &dir.join("main.umpl").display().to_string()).unwrap(),
+        Value::String("153 1".to_string())
+    );
+}
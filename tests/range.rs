@@ -0,0 +1,53 @@
+//! ranges are made lazily, one that never ends can be looped over but not made into a list
+mod common;
+
+use common::error_message;
+use umpl::{run_source, value::Value};
+
+#[test]
+fn endless_range_to_list() {
+    assert_eq!(
+        error_message("((length (range 0 (divide 1 0))))<", "range.umpl"),
+        "TokenType Length cannot turn a range that never ends into a list"
+    );
+}
+
+#[test]
+fn infinite_or_nan_bounds() {
+    assert!(error_message("((range (divide 1 0) 3))<", "range.umpl")
+        .contains("requires a finite start"));
+    assert!(error_message("((range 0 (divide 0 0)))<", "range.umpl")
+        .contains("requires a finite start"));
+    assert!(error_message("((range 0 3 (divide 1 0)))<", "range.umpl")
+        .contains("requires a finite start"));
+}
+
+#[test]
+fn loop_over_endless_range() {
+    let src = "create total with 0
+loop over i with ((range 0 (divide 1 0)))< ⧼
+    if {((eq i 4))<} ⧼ break ⧽
+    ((addwith total i))<
+⧽
+(total)<";
+    assert_eq!(run_source(src, "range.umpl").unwrap(), Value::Number(6.0));
+}
+
+#[test]
+fn decimal_bounds() {
+    let src = "((join (range `1.5` `4` `0.5`) ` `))<";
+    assert_eq!(
+        run_source(src, "range.umpl").unwrap(),
+        Value::String("1.5 2 2.5 3 3.5".to_string())
+    );
+    assert!(error_message("((range `one` 3))<", "range.umpl").contains("decimal number"));
+}
+
+#[test]
+fn fractional_step_does_not_drift() {
+    // adding 0.1 ten times comes to just under 1, which would make an eleventh number
+    let src = "((length (range 0 1 `0.1`)))<";
+    assert_eq!(run_source(src, "range.umpl").unwrap(), Value::Number(10.0));
+    let src = "((last (range 0 1 `0.1`)))<";
+    assert_eq!(run_source(src, "range.umpl").unwrap(), Value::Number(0.9));
+}
@@ -0,0 +1,38 @@
+//! a syntax error does not stop the parser, every error in the file is reported in one pass
+use umpl::{error::UmplError, run_source};
+
+fn error_messages(src: &str) -> Vec<String> {
+    match run_source(src, "recovery.umpl") {
+        Err(UmplError::Diagnostics(diagnostics)) => diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect(),
+        other => panic!("expected syntax errors for {src:?}, got {other:?}"),
+    }
+}
+
+#[test]
+fn stray_and_unclosed_code_blocks() {
+    assert_eq!(
+        error_messages("(1)>\n⧽\n(2)>\n⧼\n"),
+        ["unmatched ⧽", "unclosed code block"]
+    );
+}
+
+#[test]
+fn code_block_skipped_in_a_broken_statement() {
+    let messages = error_messages("if {true} ⧼ ((plus 1 ⧼ 2 ⧽ ⧽\n⧼\n");
+    assert_eq!(messages.len(), 2, "{messages:?}");
+    assert_eq!(messages[1], "unclosed code block");
+}
+
+#[test]
+fn several_broken_statements() {
+    let src = "create 5
+((plus 1 2))<
+list x 3
+create y with 1
+if true ⧼ ((plus 1 2))< ⧽";
+    let messages = error_messages(src);
+    assert_eq!(messages.len(), 3, "{messages:?}");
+}
@@ -0,0 +1,62 @@
+//! runtime errors become diagnostics that try can rescue
+mod common;
+
+use common::test_dir;
+use std::fs;
+use umpl::{error::UmplError, run_source, value::Value};
+
+fn assert_rescued(code: &str) {
+    let src = format!("try ⧼ {code} ⧽ rescue e ⧼ (`rescued`)< ⧽");
+    assert_eq!(
+        run_source(&src, "rescue.umpl").unwrap(),
+        Value::String("rescued".to_string()),
+        "expected {code:?} to be rescued"
+    );
+}
+
+#[test]
+fn error_keyword() {
+    assert_rescued("((error `oops`))<");
+    let src = "try ⧼ ((error `oops`))< ⧽ rescue e ⧼ ((car e))< ⧽";
+    assert_eq!(
+        run_source(src, "rescue.umpl").unwrap(),
+        Value::String("oops".to_string())
+    );
+}
+
+#[test]
+fn arithmetic_without_arguments() {
+    assert_rescued("((plus))<");
+    assert_rescued("((multiply))<");
+}
+
+#[test]
+fn set_without_arguments() {
+    assert_rescued("create x with 1 ((setwith))<");
+    assert_rescued("create x with 1 ((addwith x))<");
+}
+
+#[test]
+fn line_zero() {
+    let path = test_dir("line_zero").join("lines.txt");
+    fs::write(&path, "first\nsecond").unwrap();
+    let path = path.display();
+    assert_rescued(&format!(
+        "create f with ((open `{path}`))< ((readline f 0))<"
+    ));
+    assert_rescued(&format!(
+        "create f with ((open `{path}`))< ((writeline f `x` 0 `w`))<"
+    ));
+    assert_rescued(&format!(
+        "create f with ((open `{path}`))< ((writeline f `x` (minus 3) `a`))<"
+    ));
+}
+
+#[test]
+fn exit_is_not_rescued() {
+    let src = "try ⧼ ((exit 3))< ⧽ rescue e ⧼ (`rescued`)< ⧽";
+    assert!(matches!(
+        run_source(src, "rescue.umpl"),
+        Err(UmplError::Exit(3))
+    ));
+}
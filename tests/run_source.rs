@@ -0,0 +1,28 @@
+//! running a file gives back the value of its last top level statement
+use umpl::{run_source, value::Value};
+
+#[test]
+fn last_statement() {
+    let src = "create x with 5
+((plus x 1))<";
+    assert_eq!(run_source(src, "run.umpl").unwrap(), Value::Number(6.0));
+}
+
+#[test]
+fn declaration_and_if() {
+    assert_eq!(
+        run_source("create x with `five`", "run.umpl").unwrap(),
+        Value::String("five".to_string())
+    );
+    let src = "if {false} ⧼ (1)< ⧽ else ⧼ (2)< ⧽";
+    assert_eq!(run_source(src, "run.umpl").unwrap(), Value::Number(2.0));
+}
+
+#[test]
+fn nothing_to_give_back() {
+    assert_eq!(run_source("", "run.umpl").unwrap(), Value::Hempty);
+    assert_eq!(
+        run_source("potato 😀 0 ⧼ return 1 ⧽", "run.umpl").unwrap(),
+        Value::Hempty
+    );
+}
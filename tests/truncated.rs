@@ -0,0 +1,65 @@
+//! a file that ends in the middle of a statement is a syntax error, not a crash
+use umpl::{error::UmplError, run_source};
+
+fn assert_syntax_error(src: &str) {
+    match run_source(src, "truncated.umpl") {
+        Err(UmplError::Diagnostics(diagnostics)) => assert!(!diagnostics.is_empty()),
+        other => panic!("expected syntax errors for {src:?}, got {other:?}"),
+    }
+}
+
+#[test]
+fn unclosed_if() {
+    assert_syntax_error("if {true} ⧼ ((1))>");
+}
+
+#[test]
+fn broken_call_in_if() {
+    assert_syntax_error("if {true} ⧼ ((1 ⧽\n((plus 1 2))>\ncreate x with 5\n");
+}
+
+#[test]
+fn unfinished_else() {
+    assert_syntax_error("if {true} ⧼⧽ else");
+}
+
+#[test]
+fn unclosed_loop() {
+    assert_syntax_error("loop outer ⧼ loop ⧼ break outer");
+}
+
+#[test]
+fn unfinished_loop_over() {
+    assert_syntax_error("loop over");
+}
+
+#[test]
+fn unfinished_return() {
+    assert_syntax_error("potato 😀 0 ⧼ return");
+}
+
+#[test]
+fn unclosed_match_arm() {
+    let Err(UmplError::Diagnostics(diagnostics)) = run_source("match 1 ⧼ 1 ⧼", "truncated.umpl")
+    else {
+        panic!("expected syntax errors");
+    };
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.message == "unclosed match"));
+}
+
+#[test]
+fn unclosed_match() {
+    assert_syntax_error("match 1 ⧼ 1 ⧼⧽");
+}
+
+#[test]
+fn unclosed_try() {
+    assert_syntax_error("try ⧼");
+}
+
+#[test]
+fn try_without_rescue() {
+    assert_syntax_error("try ⧼ (1)> ⧽");
+}
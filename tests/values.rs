@@ -0,0 +1,57 @@
+//! lists that point back into themselves can be printed, compared and looped over without hanging
+mod common;
+
+use common::error_message;
+use umpl::{run_source, value::Value};
+
+const CYCLE: &str = "list x with [1 2]
+((setwith x.cdr.cdr x))<
+";
+
+fn value(src: &str) -> Value {
+    run_source(src, "values.umpl").unwrap()
+}
+
+#[test]
+fn print_a_cycle() {
+    assert_eq!(value(&format!("{CYCLE}(x)<")).to_string(), "[1 2 ...]");
+    let src = "list x with [1 2]\n((setwith x.car x))<\n(x)<";
+    assert_eq!(value(src).to_string(), "[... 2]");
+}
+
+#[test]
+fn shared_list_is_not_a_cycle() {
+    let src = "list x with [1]\nlist y with [x x]\n(y)<";
+    assert_eq!(value(src).to_string(), "[[1] [1]]");
+}
+
+#[test]
+fn compare_cycles() {
+    let src =
+        format!("{CYCLE}list y with [1 2 1 2]\n((setwith y.cdr.cdr.cdr.cdr y))<\n((eq x y))<");
+    assert_eq!(value(&src), Value::Boolean(true));
+    let src = format!("{CYCLE}list y with [1 3]\n((setwith y.cdr.cdr y))<\n((eq x y))<");
+    assert_eq!(value(&src), Value::Boolean(false));
+    assert_eq!(value(&format!("{CYCLE}((eq x x))<")), Value::Boolean(true));
+}
+
+#[test]
+fn cycle_to_list() {
+    assert!(
+        error_message(&format!("{CYCLE}((length x))<"), "values.umpl")
+            .contains("points back into itself")
+    );
+}
+
+#[test]
+fn repeat_a_string() {
+    let src = "create s with `ab`\n((multiplywith s 3))<\n(s)<";
+    assert_eq!(value(src), Value::String("ababab".to_string()));
+    for count in ["(divide 1 0)", "(minus 1)", "(divide 1 2)", "0x100000000"] {
+        let src = format!("create s with `ab`\n((multiplywith s {count}))<");
+        assert!(
+            error_message(&src, "values.umpl").contains("MultiplyWith"),
+            "{count}"
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! warnings are given back to the caller instead of being printed
+mod common;
+
+use common::test_dir;
+use std::fs;
+use umpl::{error::Severity, run_source_with_warnings};
+
+#[test]
+fn match_without_else() {
+    let (result, warnings) = run_source_with_warnings("match 1 ⧼ 1 ⧼⧽ ⧽", "warnings.umpl", &[]);
+    assert!(result.is_ok());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, Severity::Warning);
+    assert_eq!(warnings[0].message, "match is not exhaustive");
+}
+
+#[test]
+fn exhaustive_match() {
+    let src = "match 1 ⧼ type number ⧼⧽ else ⧼⧽ ⧽";
+    let (result, warnings) = run_source_with_warnings(src, "warnings.umpl", &[]);
+    assert!(result.is_ok());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn warnings_of_a_failed_run() {
+    let src = "match 1 ⧼ 1 ⧼⧽ ⧽\n((error `after the match`))<";
+    let (result, warnings) = run_source_with_warnings(src, "warnings.umpl", &[]);
+    assert!(result.is_err());
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn warnings_of_a_module() {
+    let dir = test_dir("warnings_of_a_module");
+    fs::write(dir.join("m.umpl"), "match 1 ⧼ 1 ⧼⧽ ⧽\n").unwrap();
+    let main = dir.join("main.umpl").display().to_string();
+    let (result, warnings) = run_source_with_warnings("((module `m` `m.umpl`))<", &main, &[]);
+    assert!(result.is_ok());
+    assert_eq!(warnings.len(), 1);
+}